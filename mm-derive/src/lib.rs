@@ -0,0 +1,275 @@
+//! `#[derive(RemoteStruct)]` for `mm`. Enable it through `mm`'s `derive` feature
+//! rather than depending on this crate directly; the generated code refers to `::mm`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Field, Fields, GenericArgument, LitInt, PathArguments, Type};
+
+/// Implements `mm::RemoteStruct` for a struct whose fields all carry `#[offset(..)]`.
+///
+/// * plain fields (any `mm::Pod`) are copied out of the struct's span, like `mmg::read`
+/// * `#[nested]` fields (or fixed arrays of them) are decoded from the same span
+/// * `#[pointer]` fields are `Option<T>`: the pointer is read from the span and followed
+///   with a separate read of `T`, `None` when null, within the `decode` depth budget
+///
+/// `#[remote(size = 0x40)]` on the struct fixes its size, e.g. to use it as the stride of a
+/// nested array. Also generates `<field>_address` for every field and `write_<field>` for
+/// plain and pointer fields.
+#[proc_macro_derive(RemoteStruct, attributes(offset, nested, pointer, remote))]
+pub fn derive_remote_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+enum Kind {
+    Plain,
+    Nested,
+    NestedArray { element: Type, len: Expr },
+    Pointer { target: Type },
+}
+
+struct RemoteField<'a> {
+    field: &'a Field,
+    offset: LitInt,
+    kind: Kind,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(input.generics.span(), "RemoteStruct does not support generics"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => return Err(Error::new(input.ident.span(), "RemoteStruct needs named fields")),
+        },
+        _ => return Err(Error::new(input.ident.span(), "RemoteStruct can only be derived for structs")),
+    };
+
+    let fields = fields.iter().map(parse_field).collect::<syn::Result<Vec<_>>>()?;
+    let fixed_size = struct_size(input)?;
+
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    let ends = fields.iter().map(|f| {
+        let offset = &f.offset;
+        let size = field_size(f);
+        quote! { #offset + #size }
+    });
+    let span_size = quote! {{
+        let mut size = 0usize;
+        #( let end = #ends; if end > size { size = end; } )*
+        size
+    }};
+
+    let size = match &fixed_size {
+        Some(fixed) => quote! {{
+            assert!(#span_size <= #fixed, "fields do not fit the declared size");
+            #fixed
+        }},
+        None => span_size,
+    };
+
+    let decoders = fields.iter().map(field_decoder);
+    let accessors = fields.iter().map(|f| field_accessors(f, vis));
+
+    Ok(quote! {
+        impl ::mm::RemoteStruct for #name {
+            const SIZE: usize = #size;
+
+            fn decode<B: ::mm::MemoryBackend + ?Sized>(
+                mem: &::mm::mmg<'_, B>,
+                address: usize,
+                bytes: &[u8],
+                depth: usize,
+            ) -> ::mm::Result<Self> {
+                let _ = (mem, address, depth);
+                ::core::result::Result::Ok(Self { #(#decoders,)* })
+            }
+        }
+
+        impl #name {
+            #(#accessors)*
+        }
+    })
+}
+
+fn struct_size(input: &DeriveInput) -> syn::Result<Option<LitInt>> {
+    let mut size = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("remote")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("size") {
+                size = Some(meta.value()?.parse::<LitInt>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `size = ..`"))
+            }
+        })?;
+    }
+
+    Ok(size)
+}
+
+fn parse_field(field: &Field) -> syn::Result<RemoteField<'_>> {
+    let mut offset = None;
+    let mut nested = false;
+    let mut pointer = false;
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("offset") {
+            let literal = attr.parse_args::<LitInt>()?;
+            literal.base10_parse::<usize>()?;
+            offset = Some(literal);
+        } else if attr.path().is_ident("nested") {
+            attr.meta.require_path_only()?;
+            nested = true;
+        } else if attr.path().is_ident("pointer") {
+            attr.meta.require_path_only()?;
+            pointer = true;
+        }
+    }
+
+    let offset = offset.ok_or_else(|| Error::new(field.span(), "missing #[offset(..)]"))?;
+
+    let kind = match (nested, pointer) {
+        (true, true) => return Err(Error::new(field.span(), "a field cannot be both #[nested] and #[pointer]")),
+        (true, false) => match &field.ty {
+            Type::Array(array) => Kind::NestedArray {
+                element: (*array.elem).clone(),
+                len: array.len.clone(),
+            },
+            _ => Kind::Nested,
+        },
+        (false, true) => Kind::Pointer {
+            target: option_target(&field.ty)
+                .ok_or_else(|| Error::new(field.ty.span(), "#[pointer] fields must be Option<T>"))?,
+        },
+        (false, false) => Kind::Plain,
+    };
+
+    Ok(RemoteField { field, offset, kind })
+}
+
+fn option_target(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else { return None };
+    let last = path.path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(target) => Some(target.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn field_size(field: &RemoteField<'_>) -> TokenStream2 {
+    let ty = &field.field.ty;
+
+    match &field.kind {
+        Kind::Plain => quote! { ::core::mem::size_of::<#ty>() },
+        Kind::Nested => quote! { <#ty as ::mm::RemoteStruct>::SIZE },
+        Kind::NestedArray { element, len } => quote! { <#element as ::mm::RemoteStruct>::SIZE * (#len) },
+        Kind::Pointer { .. } => quote! { ::core::mem::size_of::<usize>() },
+    }
+}
+
+fn field_decoder(field: &RemoteField<'_>) -> TokenStream2 {
+    let ident = &field.field.ident;
+    let ty = &field.field.ty;
+    let offset = &field.offset;
+
+    let value = match &field.kind {
+        Kind::Plain => quote! { ::mm::remote::decode_field::<#ty>(bytes, #offset)? },
+        Kind::Nested => quote! {
+            <#ty as ::mm::RemoteStruct>::decode(
+                mem,
+                address.wrapping_add(#offset),
+                bytes.get(#offset..).unwrap_or_default(),
+                depth,
+            )?
+        },
+        Kind::NestedArray { element, len } => quote! {{
+            let stride = <#element as ::mm::RemoteStruct>::SIZE;
+            let mut elements = ::std::vec::Vec::with_capacity(#len);
+            for index in 0..(#len) {
+                let start = #offset + index * stride;
+                elements.push(<#element as ::mm::RemoteStruct>::decode(
+                    mem,
+                    address.wrapping_add(start),
+                    bytes.get(start..).unwrap_or_default(),
+                    depth,
+                )?);
+            }
+            match <#ty as ::core::convert::TryFrom<_>>::try_from(elements) {
+                ::core::result::Result::Ok(array) => array,
+                ::core::result::Result::Err(_) => unreachable!(),
+            }
+        }},
+        Kind::Pointer { target } => quote! {
+            match ::mm::remote::decode_field::<usize>(bytes, #offset)? {
+                0 => ::core::option::Option::None,
+                pointer if depth == 0 => return ::core::result::Result::Err(::mm::mm_error::PointerDepthExceeded(pointer)),
+                pointer => ::core::option::Option::Some(
+                    <#target as ::mm::RemoteStruct>::read_with_depth(mem, pointer, depth - 1)?,
+                ),
+            }
+        },
+    };
+
+    quote! { #ident: #value }
+}
+
+fn field_accessors(field: &RemoteField<'_>, vis: &syn::Visibility) -> TokenStream2 {
+    let ident = field.field.ident.as_ref().expect("named field");
+    let ty = &field.field.ty;
+    let offset = &field.offset;
+
+    let address_fn = format_ident!("{}_address", ident.to_string().trim_start_matches("r#"), span = ident.span());
+    let write_fn = format_ident!("write_{}", ident.to_string().trim_start_matches("r#"), span = ident.span());
+
+    let address_doc = format!("Address of `{}` in a struct at `address`.", ident);
+    let address = quote! {
+        #[doc = #address_doc]
+        #[must_use]
+        #[inline]
+        #vis const fn #address_fn(address: usize) -> usize {
+            address.wrapping_add(#offset)
+        }
+    };
+
+    let write_doc = format!("Writes `{}` of the struct at `address`.", ident);
+    let write = match &field.kind {
+        Kind::Plain => quote! {
+            #[doc = #write_doc]
+            #vis fn #write_fn<B: ::mm::MemoryBackend + ?Sized>(
+                mem: &::mm::mmg<'_, B>,
+                address: usize,
+                value: &#ty,
+            ) -> ::mm::Result<usize> {
+                mem.write(address.wrapping_add(#offset), value)
+            }
+        },
+        Kind::Pointer { .. } => quote! {
+            #[doc = #write_doc]
+            #vis fn #write_fn<B: ::mm::MemoryBackend + ?Sized>(
+                mem: &::mm::mmg<'_, B>,
+                address: usize,
+                pointer: usize,
+            ) -> ::mm::Result<usize> {
+                mem.write(address.wrapping_add(#offset), &pointer)
+            }
+        },
+        Kind::Nested | Kind::NestedArray { .. } => TokenStream2::new(),
+    };
+
+    quote! { #address #write }
+}
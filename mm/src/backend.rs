@@ -0,0 +1,136 @@
+pub mod cache;
+pub mod core_dump;
+pub mod local;
+pub mod minidump;
+pub mod mock;
+
+use crate::error::{mm_error, Result};
+use crate::module::ModuleInfo;
+use crate::region::{MemoryRegion, Protection};
+
+/// Raw access to some address space. `mmg` and `TypeReader` are built on top of this,
+/// so anything implementing it gets the full typed read/write API.
+pub trait MemoryBackend {
+    /// Reads into `buffer`, returning the number of bytes actually copied.
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize>;
+
+    /// Writes `buffer`, returning the number of bytes actually copied.
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize>;
+
+    /// Serves many reads at once, returning one result per request in the same order.
+    /// The default merges requests that are exactly adjacent into a single
+    /// `read_memory` and only falls back to per-request reads when a merged read comes up short.
+    fn read_scatter(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize>> {
+        let mut results: Vec<Result<usize>> = requests.iter().map(|_| Ok(0)).collect();
+        let mut order: Vec<usize> = (0..requests.len()).collect();
+        order.sort_by_key(|&i| requests[i].0);
+
+        let mut group_start = 0;
+        while group_start < order.len() {
+            let base = requests[order[group_start]].0;
+            let mut end = base.wrapping_add(requests[order[group_start]].1.len());
+            let mut group_end = group_start + 1;
+
+            while group_end < order.len() && requests[order[group_end]].0 == end {
+                end = end.wrapping_add(requests[order[group_end]].1.len());
+                group_end += 1;
+            }
+
+            let group = &order[group_start..group_end];
+            let mut merged = vec![0u8; end.saturating_sub(base)];
+            let read = if group.len() > 1 && !merged.is_empty() {
+                self.read_memory(base, &mut merged).unwrap_or(0)
+            } else {
+                0
+            };
+
+            for &i in group {
+                let (address, buffer) = &mut requests[i];
+                let offset = *address - base;
+
+                results[i] = if buffer.is_empty() {
+                    Ok(0)
+                } else if offset + buffer.len() <= read {
+                    buffer.copy_from_slice(&merged[offset..offset + buffer.len()]);
+                    Ok(buffer.len())
+                } else {
+                    self.read_memory(*address, buffer)
+                };
+            }
+
+            group_start = group_end;
+        }
+
+        results
+    }
+
+    /// Describes the region containing `address`.
+    fn query_region(&self, _address: usize) -> Result<MemoryRegion> {
+        Err(mm_error::Unsupported("query_region"))
+    }
+
+    /// Changes the protection of a range, returning the previous protection.
+    fn protect_region(&self, _address: usize, _size: usize, _protection: Protection) -> Result<Protection> {
+        Err(mm_error::Unsupported("protect_region"))
+    }
+
+    /// Every region in address order, free gaps included. The default walks
+    /// `query_region` from address zero until the address space is exhausted.
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut result = vec![self.query_region(0)?];
+
+        while let Some(last) = result.last() {
+            let cursor = last.end_address();
+            if cursor <= last.base || cursor == usize::MAX {
+                break;
+            }
+            match self.query_region(cursor) {
+                Ok(region) if region.end_address() > cursor => result.push(region),
+                _ => break,
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        Err(mm_error::Unsupported("enumerate_modules"))
+    }
+}
+
+impl<B: MemoryBackend + ?Sized> MemoryBackend for &B {
+    #[inline]
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        (**self).read_memory(address, buffer)
+    }
+
+    #[inline]
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize> {
+        (**self).write_memory(address, buffer)
+    }
+
+    #[inline]
+    fn read_scatter(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize>> {
+        (**self).read_scatter(requests)
+    }
+
+    #[inline]
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        (**self).query_region(address)
+    }
+
+    #[inline]
+    fn protect_region(&self, address: usize, size: usize, protection: Protection) -> Result<Protection> {
+        (**self).protect_region(address, size, protection)
+    }
+
+    #[inline]
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        (**self).regions()
+    }
+
+    #[inline]
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        (**self).enumerate_modules()
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::backend::MemoryBackend;
+use crate::error::Result;
+use crate::module::ModuleInfo;
+use crate::region::{MemoryRegion, Protection};
+
+const DEFAULT_PAGE_SIZE: usize = 0x1000;
+const DEFAULT_CAPACITY: usize = 4096;
+
+#[derive(Debug)]
+struct CachedPage {
+    /// Readable prefix of the page; shorter than a page when the fetch came up short.
+    data: Box<[u8]>,
+    fetched: Instant,
+    /// `CacheState::generation` when the fetch started; older pages are stale.
+    generation: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    pages: HashMap<usize, CachedPage>,
+    /// Bumped by `invalidate_all`, which leaves the pages to be dropped as they are met.
+    generation: u64,
+    /// Bumped by every other invalidation, so a fetch that raced one is not kept.
+    invalidations: u64,
+}
+
+/// Read-through page cache over another backend. Reads fetch and keep whole pages,
+/// writes go straight through and drop the pages they touch. Pages are dropped when
+/// invalidated, go stale when they outlive the TTL or a new generation starts, and at
+/// most `capacity` are kept
+/// (stale ones are evicted first, then the oldest). Fetches run without holding the
+/// cache lock, so readers on other threads are not serialised behind them.
+///
+/// Opt-in: wrap a backend (or a reference to one) and hand the wrapper to `mmg::new`.
+#[derive(Debug)]
+pub struct CachedBackend<B> {
+    inner: B,
+    page_size: usize,
+    ttl: Option<Duration>,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl<B: MemoryBackend> CachedBackend<B> {
+    #[must_use]
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            page_size: DEFAULT_PAGE_SIZE,
+            ttl: None,
+            capacity: DEFAULT_CAPACITY,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// # Panics
+    /// If `page_size` is not a power of two.
+    #[must_use]
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size.is_power_of_two(), "page size must be a power of two");
+        self.page_size = page_size;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Most pages kept at once, 4096 by default.
+    #[must_use]
+    pub fn with_capacity(mut self, pages: usize) -> Self {
+        self.capacity = pages.max(1);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Starts a new generation, making every cached page stale, and returns it. Stale
+    /// pages are refetched on their next read and evicted first.
+    pub fn invalidate_all(&self) -> u64 {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.generation
+    }
+
+    pub fn invalidate_page(&self, address: usize) {
+        let base = self.page_base(address);
+        let mut state = self.lock();
+        state.pages.remove(&base);
+        state.invalidations += 1;
+    }
+
+    /// Drops every page overlapping `[address, address + len)`.
+    pub fn invalidate_range(&self, address: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+
+        let first = self.page_base(address);
+        let last = self.page_base(address.saturating_add(len - 1));
+        let mut state = self.lock();
+        state.pages.retain(|&base, _| base < first || base > last);
+        state.invalidations += 1;
+    }
+
+    /// Frees every cached page.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.pages.clear();
+        state.invalidations += 1;
+    }
+
+    /// Pages of the current generation held, fresh or past their TTL.
+    #[must_use]
+    pub fn cached_pages(&self) -> usize {
+        let state = self.lock();
+        state.pages.values().filter(|page| page.generation == state.generation).count()
+    }
+
+    #[inline]
+    const fn page_base(&self, address: usize) -> usize {
+        address & !(self.page_size - 1)
+    }
+
+    fn is_fresh(&self, page: &CachedPage, generation: u64) -> bool {
+        page.generation == generation && self.ttl.is_none_or(|ttl| page.fetched.elapsed() < ttl)
+    }
+
+    /// Makes room for one more page: stale pages go first, then the oldest fetched.
+    fn evict(&self, state: &mut CacheState) {
+        if state.pages.len() < self.capacity {
+            return;
+        }
+
+        let generation = state.generation;
+        state.pages.retain(|_, page| self.is_fresh(page, generation));
+        if state.pages.len() >= self.capacity
+            && let Some(oldest) = state.pages.iter().min_by_key(|(_, page)| page.fetched).map(|(&base, _)| base)
+        {
+            state.pages.remove(&oldest);
+        }
+    }
+
+    /// Copies from the page at `base` (fetching it when missing or stale) into `buffer`,
+    /// returning how many bytes the page could supply from `offset`.
+    fn copy_from_page(&self, base: usize, offset: usize, buffer: &mut [u8]) -> usize {
+        let (generation, invalidations) = {
+            let state = self.lock();
+            if let Some(page) = state.pages.get(&base).filter(|page| self.is_fresh(page, state.generation)) {
+                return copy_out(&page.data, offset, buffer);
+            }
+            (state.generation, state.invalidations)
+        };
+
+        let mut data = vec![0u8; self.page_size];
+        let read = self.inner.read_memory(base, &mut data);
+        let mut state = self.lock();
+
+        match read {
+            Ok(read) if read > 0 => {
+                data.truncate(read);
+                let len = copy_out(&data, offset, buffer);
+
+                // a write or invalidation may have landed while the page was being fetched;
+                // a new generation is caught by the tag instead
+                if state.invalidations == invalidations {
+                    self.evict(&mut state);
+                    let page = CachedPage {
+                        data: data.into_boxed_slice(),
+                        fetched: Instant::now(),
+                        generation,
+                    };
+                    state.pages.insert(base, page);
+                }
+                len
+            }
+            _ => {
+                state.pages.remove(&base);
+                0
+            }
+        }
+    }
+}
+
+fn copy_out(data: &[u8], offset: usize, buffer: &mut [u8]) -> usize {
+    let available = data.get(offset..).unwrap_or_default();
+    let len = available.len().min(buffer.len());
+    buffer[..len].copy_from_slice(&available[..len]);
+    len
+}
+
+impl<B: MemoryBackend> MemoryBackend for CachedBackend<B> {
+    /// Served from cached pages. When not even the first byte can come from the
+    /// cache the read goes to the inner backend, so errors are the same as uncached.
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        let mut copied = 0;
+
+        while copied < buffer.len() {
+            let cursor = address.wrapping_add(copied);
+            let base = self.page_base(cursor);
+            let offset = cursor - base;
+
+            let len = self.copy_from_page(base, offset, &mut buffer[copied..]);
+            copied += len;
+
+            if offset + len < self.page_size {
+                break;
+            }
+        }
+
+        if copied == 0 && !buffer.is_empty() {
+            return self.inner.read_memory(address, buffer);
+        }
+
+        Ok(copied)
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize> {
+        let result = self.inner.write_memory(address, buffer);
+        self.invalidate_range(address, buffer.len());
+        result
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        self.inner.query_region(address)
+    }
+
+    fn protect_region(&self, address: usize, size: usize, protection: Protection) -> Result<Protection> {
+        let result = self.inner.protect_region(address, size, protection);
+        self.invalidate_range(address, size);
+        result
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        self.inner.regions()
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        self.inner.enumerate_modules()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::error::mm_error;
+    use crate::memory::mmg;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map(0x1000, (0..0x2000).map(|i| i as u8).collect::<Vec<_>>(), Protection::READ_WRITE);
+        mock
+    }
+
+    #[test]
+    fn repeated_reads_hit_the_cache() {
+        let mock = process();
+        let cache = CachedBackend::new(&mock);
+        let mem = mmg::new(&cache);
+
+        for _ in 0..10 {
+            assert_eq!(mem.read::<u32>(0x1010).unwrap(), 0x1312_1110);
+        }
+        assert_eq!(mem.read::<u16>(0x1FFF).unwrap(), 0x00FF);
+
+        let fetched: Vec<_> = mock.reads().iter().map(|r| (r.address, r.len)).collect();
+        assert_eq!(fetched, [(0x1000, 0x1000), (0x2000, 0x1000)]);
+        assert_eq!(cache.cached_pages(), 2);
+    }
+
+    #[test]
+    fn writes_and_explicit_invalidation_refresh_pages() {
+        let mock = process();
+        let cache = CachedBackend::new(&mock);
+        let mem = mmg::new(&cache);
+
+        assert_eq!(mem.read::<u8>(0x1001).unwrap(), 0x01);
+        mem.write::<u8>(0x1001, &0xAA).unwrap();
+        assert_eq!(mem.read::<u8>(0x1001).unwrap(), 0xAA);
+
+        mmg::new(&mock).write::<u8>(0x1002, &0xBB).unwrap();
+        assert_eq!(mem.read::<u8>(0x1002).unwrap(), 0x02);
+
+        cache.invalidate_page(0x1FFF);
+        assert_eq!(mem.read::<u8>(0x1002).unwrap(), 0xBB);
+
+        mmg::new(&mock).write::<u8>(0x1003, &0xCC).unwrap();
+        let generation = cache.generation();
+        assert_eq!(cache.invalidate_all(), generation + 1);
+        assert_eq!(cache.cached_pages(), 0);
+        assert_eq!(mem.read::<u8>(0x1003).unwrap(), 0xCC);
+        assert_eq!((cache.cached_pages(), mock.reads().last().map(|r| r.address)), (1, Some(0x1000)));
+    }
+
+    #[test]
+    fn capacity_evicts_the_oldest_page() {
+        let mock = process();
+        let cache = CachedBackend::new(&mock).with_page_size(0x800).with_capacity(2);
+        let mem = mmg::new(&cache);
+
+        for address in [0x1000, 0x1800, 0x2000] {
+            mem.read::<u8>(address).unwrap();
+        }
+        assert_eq!(cache.cached_pages(), 2);
+
+        mock.clear_accesses();
+        mem.read::<u8>(0x2000).unwrap();
+        mem.read::<u8>(0x1800).unwrap();
+        assert!(mock.reads().is_empty());
+        mem.read::<u8>(0x1000).unwrap();
+        assert_eq!(mock.reads().len(), 1);
+    }
+
+    #[test]
+    fn expired_pages_are_refetched() {
+        let mock = process();
+        let cache = CachedBackend::new(&mock).with_ttl(Duration::ZERO);
+        let mem = mmg::new(&cache);
+
+        mem.read::<u8>(0x1000).unwrap();
+        mem.read::<u8>(0x1000).unwrap();
+        assert_eq!(mock.reads().len(), 2);
+    }
+
+    #[test]
+    fn unreadable_memory_fails_like_the_inner_backend() {
+        let mock = process();
+        let cache = CachedBackend::new(&mock).with_page_size(0x800);
+        let mem = mmg::new(&cache);
+
+        assert!(matches!(mem.read::<u8>(0x8000), Err(mm_error::MemoryNotAccessable(0x8000))));
+        assert_eq!(mem.read_bytes_vec(0x2FFC, 8).unwrap(), [0xFC, 0xFD, 0xFE, 0xFF]);
+        assert_eq!(cache.cached_pages(), 1);
+    }
+}
@@ -0,0 +1,328 @@
+use std::path::Path;
+
+use crate::backend::MemoryBackend;
+use crate::elf::{modules_from_mappings, ElfHeader, ET_CORE, NT_FILE, PT_LOAD, PT_NOTE};
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::region::{MemoryRegion, Protection};
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: usize,
+    size: usize,
+    file_offset: usize,
+    file_size: usize,
+    protection: Protection,
+}
+
+impl Segment {
+    /// Cannot overflow, `from_bytes` rejects segments that wrap the address space.
+    const fn end_address(&self) -> usize {
+        self.start + self.size
+    }
+}
+
+/// Read-only view of an ELF core file. Memory comes from the `PT_LOAD` segments,
+/// modules from the `NT_FILE` note. Segments the kernel did not dump (file size 0)
+/// are reported but unreadable.
+#[derive(Debug)]
+pub struct CoreDump {
+    data: Vec<u8>,
+    segments: Vec<Segment>,
+    modules: Vec<ModuleInfo>,
+}
+
+impl CoreDump {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| mm_error::DumpLoadFailed(format!("{} -> {}", path.display(), e)))?;
+        Self::from_bytes(data)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let invalid = |reason: &str| mm_error::DumpLoadFailed(format!("core -> {}", reason));
+
+        let elf = ElfHeader::parse(&data).ok_or_else(|| invalid("not an elf file"))?;
+        if elf.e_type != ET_CORE {
+            return Err(invalid("elf file is not a core dump"));
+        }
+
+        let table = usize::try_from(elf.phoff)
+            .ok()
+            .and_then(|at| data.get(at..at.checked_add(elf.program_headers_size())?))
+            .ok_or_else(|| invalid("truncated program header table"))?;
+        let program_headers = elf.program_headers(table);
+
+        let mut segments = program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PT_LOAD && ph.p_memsz > 0)
+            .map(|ph| {
+                let start = usize::try_from(ph.p_vaddr).ok();
+                let size = usize::try_from(ph.p_memsz).ok();
+                let Some((start, size)) = start.zip(size).filter(|&(start, size)| start.checked_add(size).is_some()) else {
+                    return Err(invalid("load segment wraps the address space"));
+                };
+
+                let file_offset = (ph.p_offset as usize).min(data.len());
+                Ok(Segment {
+                    start,
+                    size,
+                    file_offset,
+                    file_size: (ph.p_filesz.min(ph.p_memsz) as usize).min(data.len() - file_offset),
+                    protection: ph.protection(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        segments.sort_by_key(|s| s.start);
+
+        let mut dump = Self {
+            data,
+            segments,
+            modules: Vec::new(),
+        };
+
+        let mut file_mappings = Vec::new();
+        for ph in program_headers.iter().filter(|ph| ph.p_type == PT_NOTE) {
+            let (at, len) = (ph.p_offset as usize, ph.p_filesz as usize);
+            let Some(bytes) = dump.data.get(at..at.saturating_add(len)) else {
+                continue;
+            };
+
+            for note in elf.notes(bytes) {
+                if note.n_type == NT_FILE && note.name == b"CORE" {
+                    file_mappings.extend(elf.file_note(note.desc));
+                }
+            }
+        }
+
+        dump.modules = modules_from_mappings(file_mappings, &mmg::new(&dump));
+        Ok(dump)
+    }
+
+    fn segment_containing(&self, address: usize) -> Option<&Segment> {
+        let index = self.segments.partition_point(|s| s.start <= address).checked_sub(1)?;
+        self.segments.get(index).filter(|s| address < s.end_address())
+    }
+}
+
+impl MemoryBackend for CoreDump {
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        let mut copied = 0;
+
+        while copied < buffer.len() {
+            let cursor = address.wrapping_add(copied);
+            let Some(segment) = self.segment_containing(cursor) else {
+                break;
+            };
+
+            let offset = cursor - segment.start;
+            if offset >= segment.file_size {
+                break;
+            }
+
+            let take = (segment.file_size - offset).min(buffer.len() - copied);
+            let from = segment.file_offset + offset;
+            buffer[copied..copied + take].copy_from_slice(&self.data[from..from + take]);
+            copied += take;
+        }
+
+        if copied == 0 && !buffer.is_empty() {
+            return Err(mm_error::MemoryNotAccessable(address));
+        }
+
+        Ok(copied)
+    }
+
+    fn write_memory(&self, _address: usize, _buffer: &[u8]) -> Result<usize> {
+        Err(mm_error::Unsupported("write_memory on a core dump"))
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        if let Some(segment) = self.segment_containing(address) {
+            let protection = if segment.file_size == 0 {
+                Protection::NONE
+            } else {
+                segment.protection
+            };
+            return Ok(MemoryRegion::new(segment.start, segment.size, protection));
+        }
+
+        let gap_start = self
+            .segments
+            .iter()
+            .map(Segment::end_address)
+            .filter(|&end| end <= address)
+            .max()
+            .unwrap_or(0);
+        let gap_end = self
+            .segments
+            .iter()
+            .map(|s| s.start)
+            .find(|&start| start > address)
+            .unwrap_or(usize::MAX);
+
+        Ok(MemoryRegion::free(gap_start, gap_end - gap_start))
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        Ok(self.modules.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(vaddr, p_flags, memsz, bytes)`; `bytes` shorter than `memsz` models an undumped tail.
+    type TestSegment<'a> = (u64, u32, u64, &'a [u8]);
+
+    fn build_core(segments: &[TestSegment<'_>], files: &[(u64, u64, u64, &str)]) -> Vec<u8> {
+        let mut note_desc = Vec::new();
+        note_desc.extend((files.len() as u64).to_le_bytes());
+        note_desc.extend(0x1000u64.to_le_bytes());
+        for &(start, end, page_offset, _) in files {
+            note_desc.extend(start.to_le_bytes());
+            note_desc.extend(end.to_le_bytes());
+            note_desc.extend(page_offset.to_le_bytes());
+        }
+        for &(.., path) in files {
+            note_desc.extend(path.as_bytes());
+            note_desc.push(0);
+        }
+        note_desc.resize(note_desc.len().next_multiple_of(4), 0);
+
+        let mut note = Vec::new();
+        note.extend(5u32.to_le_bytes());
+        note.extend((note_desc.len() as u32).to_le_bytes());
+        note.extend(NT_FILE.to_le_bytes());
+        note.extend(b"CORE\0\0\0\0");
+        note.extend(&note_desc);
+
+        let phnum = segments.len() + 1;
+        let mut cursor = 64 + phnum * 56;
+        let mut out = vec![0u8; cursor];
+
+        out[..4].copy_from_slice(b"\x7FELF");
+        out[4] = 2;
+        out[5] = 1;
+        out[6] = 1;
+        out[0x10..0x12].copy_from_slice(&ET_CORE.to_le_bytes());
+        out[0x12..0x14].copy_from_slice(&0x3Eu16.to_le_bytes());
+        out[0x20..0x28].copy_from_slice(&64u64.to_le_bytes());
+        out[0x34..0x36].copy_from_slice(&64u16.to_le_bytes());
+        out[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+        out[0x38..0x3A].copy_from_slice(&(phnum as u16).to_le_bytes());
+
+        let write_ph = |out: &mut Vec<u8>, index: usize, p_type: u32, flags: u32, offset: usize, vaddr: u64, filesz: usize, memsz: u64| {
+            let at = 64 + index * 56;
+            out[at..at + 4].copy_from_slice(&p_type.to_le_bytes());
+            out[at + 4..at + 8].copy_from_slice(&flags.to_le_bytes());
+            out[at + 8..at + 16].copy_from_slice(&(offset as u64).to_le_bytes());
+            out[at + 16..at + 24].copy_from_slice(&vaddr.to_le_bytes());
+            out[at + 32..at + 40].copy_from_slice(&(filesz as u64).to_le_bytes());
+            out[at + 40..at + 48].copy_from_slice(&memsz.to_le_bytes());
+        };
+
+        write_ph(&mut out, 0, PT_NOTE, 0, cursor, 0, note.len(), 0);
+        out.extend(&note);
+        cursor += note.len();
+
+        for (index, &(vaddr, flags, memsz, bytes)) in segments.iter().enumerate() {
+            write_ph(&mut out, index + 1, PT_LOAD, flags, cursor, vaddr, bytes.len(), memsz);
+            out.extend(bytes);
+            cursor += bytes.len();
+        }
+
+        out
+    }
+
+    fn image_header(entry: u64) -> Vec<u8> {
+        let mut image = vec![0u8; 0x100];
+        image[..4].copy_from_slice(b"\x7FELF");
+        image[4] = 2;
+        image[5] = 1;
+        image[0x10..0x12].copy_from_slice(&crate::elf::ET_DYN.to_le_bytes());
+        image[0x18..0x20].copy_from_slice(&entry.to_le_bytes());
+        image[0x20..0x28].copy_from_slice(&64u64.to_le_bytes());
+        image[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+        image[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+        image[64..68].copy_from_slice(&PT_LOAD.to_le_bytes());
+        image
+    }
+
+    fn sample() -> CoreDump {
+        let image = image_header(0x1234);
+
+        let mut data = vec![0u8; 0x40];
+        data[..8].copy_from_slice(&0x60_0010u64.to_le_bytes());
+        data[0x10 + 8..0x10 + 16].copy_from_slice(&0x60_0030u64.to_le_bytes());
+        data[0x30..0x36].copy_from_slice(b"dumped");
+
+        let core = build_core(
+            &[
+                (0x40_0000, crate::elf::PF_R | crate::elf::PF_X, 0x100, &image),
+                (0x60_0000, crate::elf::PF_R | crate::elf::PF_W, 0x40, &data),
+                (0x70_0000, crate::elf::PF_R, 0x1000, &[]),
+            ],
+            &[
+                (0x40_0000, 0x40_0100, 0, "/usr/bin/app"),
+                (0x60_0000, 0x60_0040, 2, "/usr/bin/app"),
+                (0x70_0000, 0x70_1000, 0, "/usr/lib/libfoo.so.1"),
+            ],
+        );
+
+        CoreDump::from_bytes(core).unwrap()
+    }
+
+    #[test]
+    fn serves_reads_from_load_segments() {
+        let dump = sample();
+        let mem = mmg::new(&dump);
+
+        assert_eq!(mem.read_pointer_chain(0x60_0000, &[0, 8, 0]).unwrap(), 0x60_0030);
+        assert_eq!(mem.read_string(0x60_0030, 16).unwrap(), "dumped");
+        assert_eq!(mem.read_bytes_vec(0x60_0038, 0x100).unwrap().len(), 8);
+        assert!(matches!(mem.read::<u8>(0x70_0000), Err(mm_error::MemoryNotAccessable(_))));
+        assert!(matches!(mem.read::<u8>(0x50_0000), Err(mm_error::MemoryNotAccessable(_))));
+        assert!(matches!(mem.write::<u8>(0x60_0000, &0), Err(mm_error::Unsupported(_))));
+    }
+
+    #[test]
+    fn wrapping_segments_are_rejected() {
+        let core = build_core(&[(u64::MAX - 0x10, crate::elf::PF_R, 0x100, &[0; 0x10])], &[]);
+        assert!(matches!(CoreDump::from_bytes(core), Err(mm_error::DumpLoadFailed(_))));
+    }
+
+    #[test]
+    fn modules_come_from_nt_file() {
+        let dump = sample();
+        let mem = mmg::new(&dump);
+        let modules = mem.enumerate_modules().unwrap();
+
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0], ModuleInfo::new("app".into(), 0x40_0000, 0x20_0040, 0x40_1234));
+        assert_eq!(modules[1].name, "libfoo.so.1");
+        assert_eq!(modules[1].entry_point, 0);
+        assert_eq!(mem.get_module_base("libfoo.so.1").unwrap(), 0x70_0000);
+    }
+
+    #[test]
+    fn regions_reflect_segments() {
+        let dump = sample();
+
+        let text = dump.query_region(0x40_0010).unwrap();
+        assert_eq!((text.base, text.size, text.protection), (0x40_0000, 0x100, Protection::READ_EXECUTE));
+        assert_eq!(dump.query_region(0x70_0000).unwrap().protection, Protection::NONE);
+
+        let gap = dump.query_region(0x50_0000).unwrap();
+        assert_eq!((gap.base, gap.end_address()), (0x40_0100, 0x60_0000));
+    }
+
+    #[test]
+    fn rejects_non_core_files() {
+        assert!(matches!(CoreDump::from_bytes(b"nope".to_vec()), Err(mm_error::DumpLoadFailed(_))));
+        assert!(matches!(CoreDump::from_bytes(image_header(0)), Err(mm_error::DumpLoadFailed(_))));
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(target_os = "linux")]
+use std::sync::RwLock;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+#[cfg(target_os = "linux")]
+use crate::procfs::MapsEntry;
+use crate::region::{MemoryRegion, Protection};
+#[cfg(target_os = "linux")]
+use crate::region::RegionState;
+
+/// The calling process's own address space. Every access is checked against the
+/// current mappings first, so bad addresses come back as errors instead of faults.
+///
+/// On Linux the mappings are a snapshot of `/proc/self/maps`, taken on first use and
+/// refreshed only when an address falls outside every mapping in it (or on `refresh`).
+#[derive(Debug)]
+pub struct CurrentProcess {
+    #[cfg(target_os = "linux")]
+    maps: RwLock<Vec<MapsEntry>>,
+}
+
+impl CurrentProcess {
+    /// # Safety
+    /// Accesses are plain copies within this process, so the mapping check cannot make
+    /// them sound. For as long as the backend is used, the caller must ensure that:
+    /// - every address range it reads or writes stays mapped with that access allowed
+    ///   (another thread can unmap or reprotect memory between the check and the copy);
+    /// - no write touches memory behind a live `&`/`&mut` reference or otherwise owned by
+    ///   Rust code that does not expect it, and no read races such a write.
+    #[must_use]
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            maps: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Re-reads the mappings snapshot.
+    #[cfg(target_os = "linux")]
+    pub fn refresh(&self) -> Result<()> {
+        let maps = crate::procfs::read_maps("self")
+            .map_err(|e| mm_error::ReadFailed { address: 0, status: e.raw_os_error().unwrap_or(libc::EIO) })?;
+
+        *self.maps.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = maps;
+        Ok(())
+    }
+
+    /// Nothing is cached on Windows, every lookup is a `VirtualQuery`.
+    #[cfg(windows)]
+    #[inline]
+    pub fn refresh(&self) -> Result<()> {
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn region_at(&self, address: usize) -> Result<MemoryRegion> {
+        let lookup = || crate::procfs::region_at(&self.maps.read().unwrap_or_else(|poisoned| poisoned.into_inner()), address);
+
+        let region = lookup();
+        if region.state != RegionState::Free {
+            return Ok(region);
+        }
+
+        self.refresh()?;
+        Ok(lookup())
+    }
+
+    #[cfg(windows)]
+    fn region_at(&self, address: usize) -> Result<MemoryRegion> {
+        use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+        crate::ntapi::virtual_query(unsafe { GetCurrentProcess() }, address)
+    }
+
+    fn accessible_len(&self, address: usize, len: usize, allowed: fn(Protection) -> bool) -> Result<usize> {
+        let end = address.checked_add(len).ok_or(mm_error::MemoryNotAccessable(address))?;
+        let mut cursor = address;
+
+        while cursor < end {
+            let region = self.region_at(cursor)?;
+            if !allowed(region.protection) || region.end_address() <= cursor {
+                break;
+            }
+            cursor = region.end_address();
+        }
+
+        Ok(cursor.min(end) - address)
+    }
+}
+
+impl MemoryBackend for CurrentProcess {
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        let len = self.accessible_len(address, buffer.len(), Protection::is_readable)?;
+        if len == 0 && !buffer.is_empty() {
+            return Err(mm_error::MemoryNotAccessable(address));
+        }
+
+        unsafe { std::ptr::copy(address as *const u8, buffer.as_mut_ptr(), len) };
+        Ok(len)
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize> {
+        let len = self.accessible_len(address, buffer.len(), Protection::is_writable)?;
+        if len == 0 && !buffer.is_empty() {
+            return Err(mm_error::MemoryNotAccessable(address));
+        }
+
+        unsafe { std::ptr::copy(buffer.as_ptr(), address as *mut u8, len) };
+        Ok(len)
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        self.region_at(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::mmg;
+
+    #[test]
+    fn reads_and_writes_locals() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let value: u64 = 0x1122_3344_5566_7788;
+        assert_eq!(mem.read::<u64>(&raw const value as usize).unwrap(), value);
+
+        let mut target = Box::new(0u32);
+        mem.write(&raw mut *target as usize, &0xDEAD_BEEF_u32).unwrap();
+        assert_eq!(*std::hint::black_box(&target), Box::new(0xDEAD_BEEF));
+    }
+
+    #[test]
+    fn bad_addresses_are_errors() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        assert!(matches!(mem.read::<u64>(0), Err(mm_error::MemoryNotAccessable(0))));
+        assert!(matches!(mem.read::<u8>(usize::MAX), Err(mm_error::MemoryNotAccessable(_))));
+        assert!(matches!(mem.write::<u32>(0x10, &1), Err(mm_error::MemoryNotAccessable(0x10))));
+    }
+
+    #[test]
+    fn read_only_memory_is_not_writable() {
+        static CONSTANT: [u8; 4] = [1, 2, 3, 4];
+
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+        let address = CONSTANT.as_ptr() as usize;
+
+        assert_eq!(mem.read::<[u8; 4]>(address).unwrap(), [1, 2, 3, 4]);
+        assert!(mem.write::<u8>(address, &9).is_err());
+        assert!(!backend.query_region(address).unwrap().protection.is_writable());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn code_regions_are_image_mappings() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+        let code = mem.region_at(code_regions_are_image_mappings as *const () as usize).unwrap();
+
+        assert_eq!(code.kind, crate::region::RegionKind::Image);
+        assert!(code.protection.is_executable());
+        assert_eq!(code.mapped_file.map(std::path::PathBuf::from), std::env::current_exe().ok());
+        assert!(mem.query_regions().unwrap().any(|r| r.contains_address(code.base)));
+    }
+}
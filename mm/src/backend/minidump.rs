@@ -0,0 +1,485 @@
+use std::path::Path;
+
+use crate::backend::MemoryBackend;
+use crate::elf::Reader;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::region::{MemoryRegion, Protection, RegionKind};
+
+const MINIDUMP_SIGNATURE: u32 = 0x504D_444D;
+
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const MEMORY64_LIST_STREAM: u32 = 9;
+const MEMORY_INFO_LIST_STREAM: u32 = 16;
+
+const MINIDUMP_MODULE_SIZE: usize = 108;
+const MEM_COMMIT: u32 = 0x1000;
+const MEM_PRIVATE: u32 = 0x2_0000;
+const MEM_MAPPED: u32 = 0x4_0000;
+const MEM_IMAGE: u32 = 0x100_0000;
+
+#[derive(Debug, Clone, Copy)]
+struct MemoryRange {
+    start: usize,
+    size: usize,
+    rva: usize,
+}
+
+impl MemoryRange {
+    /// Cannot overflow, `from_bytes` drops ranges that wrap the address space.
+    const fn end_address(&self) -> usize {
+        self.start + self.size
+    }
+}
+
+/// Read-only view of a Windows minidump. Memory comes from `MemoryListStream` and
+/// `Memory64ListStream`, modules from `ModuleListStream`. Parsed by hand so it
+/// works on any host.
+#[derive(Debug)]
+pub struct Minidump {
+    data: Vec<u8>,
+    ranges: Vec<MemoryRange>,
+    protections: Vec<(usize, usize, Protection, RegionKind)>,
+    modules: Vec<ModuleInfo>,
+}
+
+impl Minidump {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| mm_error::DumpLoadFailed(format!("{} -> {}", path.display(), e)))?;
+        Self::from_bytes(data)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let invalid = |reason: &str| mm_error::DumpLoadFailed(format!("minidump -> {}", reason));
+        let r = Reader::new(&data, false);
+
+        if r.u32(0) != Some(MINIDUMP_SIGNATURE) {
+            return Err(invalid("bad signature"));
+        }
+
+        let stream_count = r.u32(8).ok_or_else(|| invalid("truncated header"))? as usize;
+        let directory = r.u32(12).ok_or_else(|| invalid("truncated header"))? as usize;
+
+        let mut ranges = Vec::new();
+        let mut protections = Vec::new();
+        let mut module_list = None;
+
+        for index in 0..stream_count {
+            let at = directory + index * 12;
+            let (Some(stream_type), Some(rva)) = (r.u32(at), r.u32(at + 8)) else {
+                return Err(invalid("truncated stream directory"));
+            };
+            let rva = rva as usize;
+
+            match stream_type {
+                MEMORY_LIST_STREAM => {
+                    let count = r.u32(rva).ok_or_else(|| invalid("truncated memory list"))?;
+                    for i in 0..count as usize {
+                        let at = rva + 4 + i * 16;
+                        let (Some(start), Some(size), Some(data_rva)) = (r.u64(at), r.u32(at + 8), r.u32(at + 12)) else {
+                            return Err(invalid("truncated memory descriptor"));
+                        };
+                        ranges.push(MemoryRange {
+                            start: start as usize,
+                            size: size as usize,
+                            rva: data_rva as usize,
+                        });
+                    }
+                }
+                MEMORY64_LIST_STREAM => {
+                    let (Some(count), Some(base_rva)) = (r.u64(rva), r.u64(rva + 8)) else {
+                        return Err(invalid("truncated memory64 list"));
+                    };
+                    let mut data_rva = base_rva as usize;
+                    for i in 0..count as usize {
+                        let at = rva + 16 + i * 16;
+                        let (Some(start), Some(size)) = (r.u64(at), r.u64(at + 8)) else {
+                            return Err(invalid("truncated memory64 descriptor"));
+                        };
+                        ranges.push(MemoryRange {
+                            start: start as usize,
+                            size: size as usize,
+                            rva: data_rva,
+                        });
+                        data_rva = data_rva.saturating_add(size as usize);
+                    }
+                }
+                MEMORY_INFO_LIST_STREAM => {
+                    let (Some(header_size), Some(entry_size), Some(count)) = (r.u32(rva), r.u32(rva + 4), r.u64(rva + 8))
+                    else {
+                        return Err(invalid("truncated memory info list"));
+                    };
+                    let (header_size, entry_size) = (header_size as usize, entry_size as usize);
+                    if header_size < 16 || entry_size < 48 {
+                        return Err(invalid("bad memory info list header"));
+                    }
+
+                    // the count is untrusted, no more entries than the file can hold
+                    let entries_at = rva
+                        .checked_add(header_size)
+                        .filter(|&at| at <= data.len())
+                        .ok_or_else(|| invalid("truncated memory info list"))?;
+                    let count = usize::try_from(count).unwrap_or(usize::MAX).min((data.len() - entries_at) / entry_size);
+
+                    for i in 0..count {
+                        let at = entries_at + i * entry_size;
+                        let (Some(base), Some(size), Some(state), Some(protect), Some(kind)) =
+                            (r.u64(at), r.u64(at + 24), r.u32(at + 32), r.u32(at + 36), r.u32(at + 40))
+                        else {
+                            break;
+                        };
+                        let kind = match kind {
+                            MEM_IMAGE => RegionKind::Image,
+                            MEM_MAPPED => RegionKind::Mapped,
+                            MEM_PRIVATE => RegionKind::Private,
+                            _ => RegionKind::Unknown,
+                        };
+                        if state == MEM_COMMIT {
+                            protections.push((base as usize, size as usize, Protection::from_page_flags(protect), kind));
+                        }
+                    }
+                }
+                MODULE_LIST_STREAM => module_list = Some(rva),
+                _ => {}
+            }
+        }
+
+        ranges.retain(|range| {
+            range.size > 0
+                && range.start.checked_add(range.size).is_some()
+                && range.rva.saturating_add(range.size) <= data.len()
+        });
+        ranges.sort_by_key(|range| range.start);
+        protections.sort_by_key(|&(base, ..)| base);
+
+        let mut dump = Self {
+            data,
+            ranges,
+            protections,
+            modules: Vec::new(),
+        };
+
+        if let Some(rva) = module_list {
+            dump.modules = dump.parse_modules(rva).ok_or_else(|| invalid("truncated module list"))?;
+        }
+
+        Ok(dump)
+    }
+
+    fn parse_modules(&self, rva: usize) -> Option<Vec<ModuleInfo>> {
+        let r = Reader::new(&self.data, false);
+        let count = r.u32(rva)? as usize;
+        let reader = mmg::new(self);
+        let mut modules = Vec::with_capacity(count.min(4096));
+
+        for i in 0..count {
+            let at = rva + 4 + i * MINIDUMP_MODULE_SIZE;
+            let base = r.u64(at)? as usize;
+            let size = r.u32(at + 8)? as usize;
+            let name_rva = r.u32(at + 20)? as usize;
+
+            let path = self.read_string(name_rva).unwrap_or_default();
+            let name = path.rsplit(['\\', '/']).next().unwrap_or_default().to_string();
+            let entry_point = pe_entry_point(&reader, base).unwrap_or(0);
+
+            modules.push(ModuleInfo::new(name, base, size, entry_point));
+        }
+
+        Some(modules)
+    }
+
+    fn read_string(&self, rva: usize) -> Option<String> {
+        let len = Reader::new(&self.data, false).u32(rva)? as usize;
+        let bytes = self.data.get(rva + 4..rva + 4 + len)?;
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        String::from_utf16(&units).ok()
+    }
+
+    fn range_containing(&self, address: usize) -> Option<&MemoryRange> {
+        let index = self.ranges.partition_point(|r| r.start <= address).checked_sub(1)?;
+        self.ranges.get(index).filter(|r| address < r.end_address())
+    }
+}
+
+/// `AddressOfEntryPoint` from the PE headers at `base`, when they were captured.
+fn pe_entry_point(reader: &mmg<'_, Minidump>, base: usize) -> Option<usize> {
+    if reader.read::<u16>(base).ok()? != 0x5A4D {
+        return None;
+    }
+
+    let e_lfanew = reader.read::<u32>(base.checked_add(0x3C)?).ok()?;
+    let nt_headers = base.checked_add(e_lfanew as usize)?;
+    if reader.read::<u32>(nt_headers).ok()? != 0x0000_4550 {
+        return None;
+    }
+
+    let entry_rva = reader.read::<u32>(nt_headers.checked_add(0x18 + 0x10)?).ok()?;
+    if entry_rva == 0 {
+        return None;
+    }
+    base.checked_add(entry_rva as usize)
+}
+
+impl MemoryBackend for Minidump {
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        let mut copied = 0;
+
+        while copied < buffer.len() {
+            let cursor = address.wrapping_add(copied);
+            let Some(range) = self.range_containing(cursor) else {
+                break;
+            };
+
+            let offset = cursor - range.start;
+            let take = (range.size - offset).min(buffer.len() - copied);
+            let from = range.rva + offset;
+            buffer[copied..copied + take].copy_from_slice(&self.data[from..from + take]);
+            copied += take;
+        }
+
+        if copied == 0 && !buffer.is_empty() {
+            return Err(mm_error::MemoryNotAccessable(address));
+        }
+
+        Ok(copied)
+    }
+
+    fn write_memory(&self, _address: usize, _buffer: &[u8]) -> Result<usize> {
+        Err(mm_error::Unsupported("write_memory on a minidump"))
+    }
+
+    /// Regions are the captured memory ranges, with the protection recorded in
+    /// `MemoryInfoListStream` when present. Everything else reads as inaccessible.
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        if let Some(range) = self.range_containing(address) {
+            let (protection, kind) = self
+                .protections
+                .iter()
+                .find(|&&(base, size, ..)| range.start >= base && range.start - base < size)
+                .map_or((Protection::READ, RegionKind::Unknown), |&(.., protection, kind)| (protection, kind));
+
+            let mut region = MemoryRegion::new(range.start, range.size, protection);
+            region.kind = kind;
+            return Ok(region);
+        }
+
+        let gap_start = self
+            .ranges
+            .iter()
+            .map(MemoryRange::end_address)
+            .filter(|&end| end <= address)
+            .max()
+            .unwrap_or(0);
+        let gap_end = self
+            .ranges
+            .iter()
+            .map(|r| r.start)
+            .find(|&start| start > address)
+            .unwrap_or(usize::MAX);
+
+        Ok(MemoryRegion::free(gap_start, gap_end - gap_start))
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        Ok(self.modules.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Builder {
+        out: Vec<u8>,
+        streams: Vec<(u32, u32, u32)>,
+    }
+
+    impl Builder {
+        fn new() -> Self {
+            Self { out: vec![0u8; 32], streams: Vec::new() }
+        }
+
+        fn append(&mut self, bytes: &[u8]) -> u32 {
+            let rva = self.out.len() as u32;
+            self.out.extend(bytes);
+            rva
+        }
+
+        fn stream(&mut self, stream_type: u32, bytes: &[u8]) {
+            let rva = self.append(bytes);
+            self.streams.push((stream_type, bytes.len() as u32, rva));
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            let directory = self.out.len() as u32;
+            for (stream_type, size, rva) in std::mem::take(&mut self.streams) {
+                for value in [stream_type, size, rva] {
+                    self.out.extend(value.to_le_bytes());
+                }
+            }
+            let count = (self.out.len() as u32 - directory) / 12;
+
+            self.out[..4].copy_from_slice(&MINIDUMP_SIGNATURE.to_le_bytes());
+            self.out[4..8].copy_from_slice(&0xA793u32.to_le_bytes());
+            self.out[8..12].copy_from_slice(&count.to_le_bytes());
+            self.out[12..16].copy_from_slice(&directory.to_le_bytes());
+            self.out
+        }
+    }
+
+    fn pe_image(entry_rva: u32) -> Vec<u8> {
+        let mut image = vec![0u8; 0x200];
+        image[..2].copy_from_slice(b"MZ");
+        image[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        image[0x80..0x84].copy_from_slice(b"PE\0\0");
+        image[0x80 + 0x28..0x80 + 0x2C].copy_from_slice(&entry_rva.to_le_bytes());
+        image
+    }
+
+    fn sample() -> Minidump {
+        let mut b = Builder::new();
+
+        let image_rva = b.append(&pe_image(0x1500));
+        let mut heap = vec![0u8; 0x20];
+        heap[..8].copy_from_slice(&0x20_0010u64.to_le_bytes());
+        heap[0x10..0x18].copy_from_slice(b"minidump");
+        let heap_rva = b.append(&heap);
+
+        let mut memory_list = 1u32.to_le_bytes().to_vec();
+        memory_list.extend(0x1_4000_0000u64.to_le_bytes());
+        memory_list.extend(0x200u32.to_le_bytes());
+        memory_list.extend(image_rva.to_le_bytes());
+        b.stream(MEMORY_LIST_STREAM, &memory_list);
+
+        let stack = [0xCCu8; 0x10];
+        let mut memory64 = 1u64.to_le_bytes().to_vec();
+        memory64.extend(0u64.to_le_bytes());
+        memory64.extend(0x30_0000u64.to_le_bytes());
+        memory64.extend((stack.len() as u64).to_le_bytes());
+        let base_rva = b.out.len() + memory64.len();
+        memory64[8..16].copy_from_slice(&(base_rva as u64).to_le_bytes());
+        b.stream(MEMORY64_LIST_STREAM, &memory64);
+        b.append(&stack);
+
+        let mut info = 16u32.to_le_bytes().to_vec();
+        info.extend(48u32.to_le_bytes());
+        info.extend(1u64.to_le_bytes());
+        let mut entry = [0u8; 48];
+        entry[..8].copy_from_slice(&0x20_0000u64.to_le_bytes());
+        entry[24..32].copy_from_slice(&0x1000u64.to_le_bytes());
+        entry[32..36].copy_from_slice(&MEM_COMMIT.to_le_bytes());
+        entry[36..40].copy_from_slice(&0x04u32.to_le_bytes());
+        entry[40..44].copy_from_slice(&MEM_PRIVATE.to_le_bytes());
+        info.extend(entry);
+        b.stream(MEMORY_INFO_LIST_STREAM, &info);
+
+        let mut heap_list = 1u32.to_le_bytes().to_vec();
+        heap_list.extend(0x20_0000u64.to_le_bytes());
+        heap_list.extend((heap.len() as u32).to_le_bytes());
+        heap_list.extend(heap_rva.to_le_bytes());
+        b.stream(MEMORY_LIST_STREAM, &heap_list);
+
+        let name: Vec<u8> = "C:\\game\\Game.exe".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut name_string = (name.len() as u32).to_le_bytes().to_vec();
+        name_string.extend(&name);
+        let name_rva = b.append(&name_string);
+
+        let mut modules = 2u32.to_le_bytes().to_vec();
+        for (base, size) in [(0x1_4000_0000u64, 0x5000u32), (0x7FF0_0000_0000, 0x1000)] {
+            let mut module = [0u8; MINIDUMP_MODULE_SIZE];
+            module[..8].copy_from_slice(&base.to_le_bytes());
+            module[8..12].copy_from_slice(&size.to_le_bytes());
+            module[20..24].copy_from_slice(&name_rva.to_le_bytes());
+            modules.extend(module);
+        }
+        b.stream(MODULE_LIST_STREAM, &modules);
+
+        Minidump::from_bytes(b.finish()).unwrap()
+    }
+
+    #[test]
+    fn serves_reads_from_both_memory_lists() {
+        let dump = sample();
+        let mem = mmg::new(&dump);
+
+        assert_eq!(mem.read::<u16>(0x1_4000_0000).unwrap(), 0x5A4D);
+        assert_eq!(mem.read_pointer_chain(0x20_0000, &[0, 0]).unwrap(), 0x20_0010);
+        assert_eq!(mem.read_string(0x20_0010, 8).unwrap(), "minidump");
+        assert_eq!(mem.read_bytes_vec(0x30_0000, 0x100).unwrap(), [0xCC; 0x10]);
+        assert!(matches!(mem.read::<u8>(0x40_0000), Err(mm_error::MemoryNotAccessable(_))));
+        assert!(mem.write::<u8>(0x20_0000, &1).is_err());
+    }
+
+    #[test]
+    fn modules_come_from_module_list() {
+        let dump = sample();
+        let modules = dump.enumerate_modules().unwrap();
+
+        assert_eq!(modules[0], ModuleInfo::new("Game.exe".into(), 0x1_4000_0000, 0x5000, 0x1_4000_1500));
+        assert_eq!(modules[1].entry_point, 0);
+        assert_eq!(mmg::new(&dump).get_module_base("game.exe").unwrap(), 0x1_4000_0000);
+    }
+
+    #[test]
+    fn regions_use_memory_info_when_present() {
+        let dump = sample();
+
+        let heap = dump.query_region(0x20_0004).unwrap();
+        assert_eq!((heap.protection, heap.kind), (Protection::READ_WRITE, RegionKind::Private));
+        assert_eq!(dump.query_region(0x30_0000).unwrap().protection, Protection::READ);
+
+        let gap = dump.query_region(0x25_0000).unwrap();
+        assert_eq!((gap.base, gap.end_address(), gap.protection), (0x20_0020, 0x30_0000, Protection::NONE));
+        assert!(!gap.is_committed());
+    }
+
+    #[test]
+    fn memory_info_list_is_bounded() {
+        let info = |entry_size: u32, count: u64| {
+            let mut b = Builder::new();
+            let mut info = 16u32.to_le_bytes().to_vec();
+            info.extend(entry_size.to_le_bytes());
+            info.extend(count.to_le_bytes());
+            info.extend([0u8; 48]);
+            b.stream(MEMORY_INFO_LIST_STREAM, &info);
+            Minidump::from_bytes(b.finish())
+        };
+
+        assert!(matches!(info(0, u64::MAX), Err(mm_error::DumpLoadFailed(_))));
+        assert!(matches!(info(47, 1), Err(mm_error::DumpLoadFailed(_))));
+        assert!(info(48, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn pe_headers_near_the_address_space_end_are_ignored() {
+        let base = u64::MAX - 0x1F;
+        let mut b = Builder::new();
+        let image_rva = b.append(&pe_image(0x1500)[..0x10]);
+
+        let mut memory_list = 1u32.to_le_bytes().to_vec();
+        memory_list.extend(base.to_le_bytes());
+        memory_list.extend(0x10u32.to_le_bytes());
+        memory_list.extend(image_rva.to_le_bytes());
+        b.stream(MEMORY_LIST_STREAM, &memory_list);
+
+        let mut modules = 1u32.to_le_bytes().to_vec();
+        let mut module = [0u8; MINIDUMP_MODULE_SIZE];
+        module[..8].copy_from_slice(&base.to_le_bytes());
+        module[8..12].copy_from_slice(&0x10u32.to_le_bytes());
+        modules.extend(module);
+        b.stream(MODULE_LIST_STREAM, &modules);
+
+        let dump = Minidump::from_bytes(b.finish()).unwrap();
+        assert_eq!(dump.enumerate_modules().unwrap()[0].entry_point, 0);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(Minidump::from_bytes(vec![0; 64]), Err(mm_error::DumpLoadFailed(_))));
+        assert!(matches!(Minidump::from_bytes(b"MDMP".to_vec()), Err(mm_error::DumpLoadFailed(_))));
+    }
+}
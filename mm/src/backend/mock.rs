@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::module::ModuleInfo;
+use crate::region::{MemoryRegion, Protection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockFault {
+    /// Fails the access with `mm_error::AccessDenied`.
+    AccessDenied,
+    /// Copies everything before the faulting range, then stops short.
+    PartialCopy,
+    /// Fails the access with `ReadFailed`/`WriteFailed` carrying this status.
+    Status(i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockAccess {
+    pub kind: AccessKind,
+    pub address: usize,
+    pub len: usize,
+    /// Bytes that actually moved, empty when the access failed.
+    pub bytes: Vec<u8>,
+    pub succeeded: bool,
+}
+
+#[derive(Debug)]
+struct MockRegion {
+    data: Vec<u8>,
+    protection: Protection,
+}
+
+#[derive(Debug)]
+struct InjectedFault {
+    range: Range<usize>,
+    fault: MockFault,
+    once: bool,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    regions: BTreeMap<usize, MockRegion>,
+    modules: Vec<ModuleInfo>,
+    faults: Vec<InjectedFault>,
+    log: Vec<MockAccess>,
+}
+
+/// Sparse in-memory address space with scripted faults and an access log.
+#[derive(Debug, Default)]
+pub struct MockProcess {
+    state: Mutex<MockState>,
+}
+
+impl MockProcess {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Maps `data` at `base`. Panics if `data` is empty or the range overlaps an existing region.
+    pub fn map(&self, base: usize, data: impl Into<Vec<u8>>, protection: Protection) {
+        let data = data.into();
+        assert!(!data.is_empty(), "mock region at 0x{base:X} is empty");
+        let end = base.checked_add(data.len()).expect("mock region wraps the address space");
+        let mut state = self.lock();
+
+        let overlaps = state
+            .regions
+            .range(..end)
+            .next_back()
+            .is_some_and(|(&start, region)| start + region.data.len() > base);
+        assert!(!overlaps, "mock region 0x{base:X}..0x{end:X} overlaps an existing mapping");
+
+        state.regions.insert(base, MockRegion { data, protection });
+    }
+
+    pub fn map_zeroed(&self, base: usize, size: usize, protection: Protection) {
+        self.map(base, vec![0u8; size], protection);
+    }
+
+    pub fn unmap(&self, base: usize) -> bool {
+        self.lock().regions.remove(&base).is_some()
+    }
+
+    pub fn add_module(&self, module: ModuleInfo) {
+        self.lock().modules.push(module);
+    }
+
+    pub fn inject_fault(&self, range: Range<usize>, fault: MockFault) {
+        self.lock().faults.push(InjectedFault { range, fault, once: false });
+    }
+
+    pub fn inject_fault_once(&self, range: Range<usize>, fault: MockFault) {
+        self.lock().faults.push(InjectedFault { range, fault, once: true });
+    }
+
+    pub fn clear_faults(&self) {
+        self.lock().faults.clear();
+    }
+
+    #[must_use]
+    pub fn accesses(&self) -> Vec<MockAccess> {
+        self.lock().log.clone()
+    }
+
+    #[must_use]
+    pub fn reads(&self) -> Vec<MockAccess> {
+        self.accesses().into_iter().filter(|a| a.kind == AccessKind::Read).collect()
+    }
+
+    #[must_use]
+    pub fn writes(&self) -> Vec<MockAccess> {
+        self.accesses().into_iter().filter(|a| a.kind == AccessKind::Write).collect()
+    }
+
+    pub fn clear_accesses(&self) {
+        self.lock().log.clear();
+    }
+
+    /// Peeks at mapped bytes without going through permissions, faults or the log.
+    #[must_use]
+    pub fn snapshot(&self, address: usize, len: usize) -> Option<Vec<u8>> {
+        let bytes = self.lock().peek(address, len);
+        (bytes.len() == len).then_some(bytes)
+    }
+
+    fn access(
+        &self,
+        kind: AccessKind,
+        address: usize,
+        len: usize,
+        mut copy: impl FnMut(&mut [u8], usize),
+    ) -> Result<usize> {
+        let mut state = self.lock();
+        let result = state.transfer(kind, address, len, &mut copy);
+
+        let bytes = match &result {
+            Ok(moved) => state.peek(address, *moved),
+            Err(_) => Vec::new(),
+        };
+
+        state.log.push(MockAccess {
+            kind,
+            address,
+            len,
+            bytes,
+            succeeded: result.is_ok(),
+        });
+
+        result
+    }
+}
+
+impl MockState {
+    fn region_containing(&self, address: usize) -> Option<(usize, &MockRegion)> {
+        self.regions
+            .range(..=address)
+            .next_back()
+            .filter(|(start, region)| address < *start + region.data.len())
+            .map(|(&start, region)| (start, region))
+    }
+
+    fn peek(&self, address: usize, len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        let mut cursor = address;
+
+        while out.len() < len {
+            let Some((start, region)) = self.region_containing(cursor) else {
+                break;
+            };
+            let offset = cursor - start;
+            let take = (region.data.len() - offset).min(len - out.len());
+            out.extend_from_slice(&region.data[offset..offset + take]);
+            cursor += take;
+        }
+
+        out
+    }
+
+    fn fire_fault(&mut self, address: usize, end: usize) -> Option<(MockFault, usize)> {
+        let index = self
+            .faults
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.range.start < end && address < f.range.end)
+            .min_by_key(|(_, f)| f.range.start)
+            .map(|(index, _)| index)?;
+
+        let hit = &self.faults[index];
+        let result = (hit.fault, hit.range.start.max(address));
+        if hit.once {
+            self.faults.remove(index);
+        }
+
+        Some(result)
+    }
+
+    fn transfer(
+        &mut self,
+        kind: AccessKind,
+        address: usize,
+        len: usize,
+        copy: &mut impl FnMut(&mut [u8], usize),
+    ) -> Result<usize> {
+        let failed = |status| match kind {
+            AccessKind::Read => mm_error::ReadFailed { address, status },
+            AccessKind::Write => mm_error::WriteFailed { address, status },
+        };
+
+        let end = address.checked_add(len).ok_or(mm_error::MemoryNotAccessable(address))?;
+        let mut limit = end;
+
+        match self.fire_fault(address, end) {
+            Some((MockFault::AccessDenied, _)) => return Err(mm_error::AccessDenied),
+            Some((MockFault::Status(status), _)) => return Err(failed(status)),
+            Some((MockFault::PartialCopy, fault_at)) => limit = fault_at,
+            None => {}
+        }
+
+        let allowed = match kind {
+            AccessKind::Read => Protection::is_readable,
+            AccessKind::Write => Protection::is_writable,
+        };
+
+        let mut cursor = address;
+        while cursor < limit {
+            let Some((start, region)) = self.region_containing(cursor) else {
+                break;
+            };
+            if !allowed(region.protection) {
+                break;
+            }
+
+            let region = self.regions.get_mut(&start).expect("region looked up above");
+            let offset = cursor - start;
+            let take = (region.data.len() - offset).min(limit - cursor);
+            copy(&mut region.data[offset..offset + take], cursor - address);
+            cursor += take;
+        }
+
+        if cursor == address && len > 0 {
+            return Err(mm_error::MemoryNotAccessable(address));
+        }
+
+        Ok(cursor - address)
+    }
+}
+
+impl MemoryBackend for MockProcess {
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        self.access(AccessKind::Read, address, buffer.len(), |region, at| {
+            buffer[at..at + region.len()].copy_from_slice(region);
+        })
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize> {
+        self.access(AccessKind::Write, address, buffer.len(), |region, at| {
+            region.copy_from_slice(&buffer[at..at + region.len()]);
+        })
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        let state = self.lock();
+
+        if let Some((start, region)) = state.region_containing(address) {
+            return Ok(MemoryRegion::new(start, region.data.len(), region.protection));
+        }
+
+        let gap_start = state
+            .regions
+            .range(..=address)
+            .next_back()
+            .map_or(0, |(&start, region)| start + region.data.len());
+        let gap_end = state
+            .regions
+            .range(address..)
+            .next()
+            .map_or(usize::MAX, |(&start, _)| start);
+
+        Ok(MemoryRegion::free(gap_start, gap_end - gap_start))
+    }
+
+    /// Applies to every whole region the range touches; regions are never split.
+    fn protect_region(&self, address: usize, size: usize, protection: Protection) -> Result<Protection> {
+        let mut state = self.lock();
+        let end = address.saturating_add(size.max(1));
+
+        let first = state
+            .region_containing(address)
+            .map(|(start, _)| start)
+            .ok_or(mm_error::MemoryNotAccessable(address))?;
+
+        let mut previous = None;
+        for (_, region) in state.regions.range_mut(first..end) {
+            previous.get_or_insert(region.protection);
+            region.protection = protection;
+        }
+
+        Ok(previous.unwrap_or(Protection::NONE))
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        Ok(self.lock().modules.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::mmg;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map(0x1000, 0x1122_3344_5566_7788_u64.to_le_bytes(), Protection::READ_WRITE);
+        mock.map(0x1008, vec![0xAA; 8], Protection::READ);
+        mock.map_zeroed(0x4000, 0x100, Protection::NONE);
+        mock
+    }
+
+    #[test]
+    fn reads_span_adjacent_regions_and_stop_at_gaps() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        assert_eq!(mem.read::<u64>(0x1000).unwrap(), 0x1122_3344_5566_7788);
+        let spanning = mem.read_bytes_vec(0x1004, 12).unwrap();
+        assert_eq!(spanning[..4], [0x44, 0x33, 0x22, 0x11]);
+        assert_eq!(spanning[4..], [0xAA; 8]);
+        assert_eq!(mem.read_bytes_vec(0x100C, 32).unwrap().len(), 4);
+        assert!(matches!(mem.read::<u8>(0x2000), Err(mm_error::MemoryNotAccessable(0x2000))));
+        assert!(matches!(mem.read::<u8>(0x4000), Err(mm_error::MemoryNotAccessable(0x4000))));
+    }
+
+    #[test]
+    fn permissions_are_enforced() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        assert!(mem.write::<u32>(0x1008, &1).is_err());
+        mem.write::<u32>(0x1000, &0xCAFE_BABE).unwrap();
+        assert_eq!(mem.read::<u32>(0x1000).unwrap(), 0xCAFE_BABE);
+
+        assert_eq!(mem.protect_region(0x1008, 1, Protection::READ_WRITE).unwrap(), Protection::READ);
+        mem.write::<u32>(0x1008, &1).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "is empty")]
+    fn empty_regions_are_rejected() {
+        let mock = process();
+        mock.map(0x1000, Vec::new(), Protection::READ);
+    }
+
+    #[test]
+    fn faults_are_injected() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        mock.inject_fault_once(0x1000..0x1001, MockFault::AccessDenied);
+        assert!(matches!(mem.read::<u8>(0x1000), Err(mm_error::AccessDenied)));
+        assert!(mem.read::<u8>(0x1000).is_ok());
+
+        mock.inject_fault(0x1004..0x1008, MockFault::PartialCopy);
+        assert_eq!(mem.read_bytes_vec(0x1000, 8).unwrap().len(), 4);
+        assert!(matches!(mem.read::<u64>(0x1000), Err(mm_error::InvalidBufferSize { expected: 8, actual: 4 })));
+
+        mock.clear_faults();
+        mock.inject_fault(0x1000..0x1010, MockFault::Status(5));
+        assert!(matches!(mem.write::<u8>(0x1000, &0), Err(mm_error::WriteFailed { address: 0x1000, status: 5 })));
+    }
+
+    #[test]
+    fn accesses_are_logged() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        mem.write::<u16>(0x1000, &0xBEEF).unwrap();
+        mem.read::<u16>(0x1000).unwrap();
+        let _ = mem.read::<u8>(0x9000);
+
+        let log = mock.accesses();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[0].kind, AccessKind::Write);
+        assert_eq!(log[0].bytes, [0xEF, 0xBE]);
+        assert_eq!(mock.reads()[0].bytes, [0xEF, 0xBE]);
+        assert!(!log[2].succeeded);
+
+        mock.clear_accesses();
+        assert!(mock.accesses().is_empty());
+    }
+
+    #[test]
+    fn modules_and_regions_are_queryable() {
+        let mock = process();
+        mock.add_module(ModuleInfo::new("game.so".into(), 0x1000, 0x10, 0x1004));
+        let mem = mmg::new(&mock);
+
+        assert_eq!(mem.get_module_base("GAME.so").unwrap(), 0x1000);
+        assert!(matches!(mem.get_module_base("other"), Err(mm_error::ModuleNotFound(_))));
+
+        let gap = mem.query_region(0x2000).unwrap();
+        assert_eq!((gap.base, gap.end_address(), gap.protection), (0x1010, 0x4000, Protection::NONE));
+        assert_eq!(mock.snapshot(0x1008, 2).unwrap(), [0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn region_walk_covers_the_address_space() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let regions: Vec<_> = mem.query_regions().unwrap().collect();
+        let bounds: Vec<_> = regions.iter().map(|r| (r.base, r.is_committed())).collect();
+        assert_eq!(
+            bounds,
+            [(0, false), (0x1000, true), (0x1008, true), (0x1010, false), (0x4000, true), (0x4100, false)]
+        );
+        assert_eq!(regions.last().unwrap().end_address(), usize::MAX);
+
+        assert_eq!(mem.region_at(0x1009).unwrap().protection, Protection::READ);
+        assert!(matches!(mem.region_at(0x2000), Err(mm_error::MemoryNotAccessable(0x2000))));
+    }
+}
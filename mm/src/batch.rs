@@ -0,0 +1,161 @@
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::handle::p_handle;
+use crate::memory::mmg;
+use crate::pod::{bytes_of_mut, Pod};
+
+#[derive(Debug)]
+struct Request<'v> {
+    address: usize,
+    buffer: &'v mut [u8],
+    exact: bool,
+}
+
+/// Reads queued with `queue`/`queue_bytes` and served together by `execute`,
+/// through the backend's `read_scatter`.
+#[derive(Debug)]
+pub struct ReadBatch<'a, 'v, B: ?Sized = p_handle> {
+    backend: &'a B,
+    requests: Vec<Request<'v>>,
+}
+
+impl<'a, 'v, B: MemoryBackend + ?Sized> ReadBatch<'a, 'v, B> {
+    #[must_use]
+    pub const fn new(backend: &'a B) -> Self {
+        Self {
+            backend,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Queues a typed read into `value`, returning its index in the `execute` results.
+    /// A short read is reported as `InvalidBufferSize`, as with `mmg::read`.
+    pub fn queue<T: Pod>(&mut self, address: usize, value: &'v mut T) -> usize {
+        self.push(address, bytes_of_mut(value), true)
+    }
+
+    /// Queues a raw read; a short read is reported as `Ok` with the byte count.
+    pub fn queue_bytes(&mut self, address: usize, buffer: &'v mut [u8]) -> usize {
+        self.push(address, buffer, false)
+    }
+
+    fn push(&mut self, address: usize, buffer: &'v mut [u8], exact: bool) -> usize {
+        self.requests.push(Request { address, buffer, exact });
+        self.requests.len() - 1
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Runs every queued read and returns one result per entry, in queue order.
+    pub fn execute(self) -> Vec<Result<usize>> {
+        let expected: Vec<_> = self.requests.iter().map(|r| (r.exact, r.buffer.len())).collect();
+        let mut requests: Vec<(usize, &mut [u8])> = self.requests.into_iter().map(|r| (r.address, r.buffer)).collect();
+
+        self.backend
+            .read_scatter(&mut requests)
+            .into_iter()
+            .zip(expected)
+            .map(|(result, (exact, size))| match result {
+                Ok(read) if exact && read != size => Err(mm_error::InvalidBufferSize {
+                    expected: size,
+                    actual: read,
+                }),
+                other => other,
+            })
+            .collect()
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    #[must_use]
+    #[inline]
+    pub fn batch<'v>(&self) -> ReadBatch<'a, 'v, B> {
+        ReadBatch::new(self.backend())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    #[test]
+    fn adjacent_requests_are_merged_and_results_are_per_entry() {
+        let mock = MockProcess::new();
+        mock.map(0x1000, (0u8..0x20).collect::<Vec<_>>(), Protection::READ);
+        mock.map(0x2000, vec![0xAA; 2], Protection::READ);
+        let mem = mmg::new(&mock);
+
+        let (mut a, mut b, mut c, mut missing) = (0u32, 0u16, 0u32, 0u64);
+        let mut tail = [0u8; 4];
+
+        let mut batch = mem.batch();
+        batch.queue(0x1004, &mut b);
+        batch.queue(0x1000, &mut a);
+        batch.queue(0x1006, &mut c);
+        let missing_at = batch.queue(0x3000, &mut missing);
+        let tail_at = batch.queue_bytes(0x2000, &mut tail);
+        let results = batch.execute();
+
+        assert!(results[..3].iter().all(Result::is_ok));
+        assert!(matches!(results[missing_at], Err(mm_error::MemoryNotAccessable(0x3000))));
+        assert_eq!(results[tail_at].as_ref().unwrap(), &2);
+        assert_eq!((a, b, c), (0x0302_0100, 0x0504, 0x0908_0706));
+        assert_eq!(tail, [0xAA, 0xAA, 0, 0]);
+
+        let reads: Vec<_> = mock.reads().iter().map(|r| (r.address, r.len)).collect();
+        assert_eq!(reads, [(0x1000, 10), (0x2000, 4), (0x3000, 8)]);
+    }
+
+    #[test]
+    fn short_typed_reads_are_errors() {
+        let mock = MockProcess::new();
+        mock.map(0x1000, vec![1, 2], Protection::READ);
+        let mem = mmg::new(&mock);
+
+        let mut value = 0u32;
+        let mut batch = mem.batch();
+        batch.queue(0x1000, &mut value);
+
+        let results = batch.execute();
+        assert!(matches!(results[0], Err(mm_error::InvalidBufferSize { expected: 4, actual: 2 })));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_handle_serves_large_batches_around_faults() {
+        let handle = p_handle::open_by_pid(std::process::id()).unwrap();
+        let mem = mmg::new(&handle);
+
+        let values: Vec<u64> = (0..2000).map(|i| i * 3).collect();
+        let mut out = vec![0u64; values.len()];
+        let mut unmapped = 0u32;
+
+        let mut batch = mem.batch();
+        let mut slots = out.iter_mut();
+        for (i, slot) in slots.by_ref().take(1000).enumerate() {
+            batch.queue(&raw const values[i] as usize, slot);
+        }
+        let bad = batch.queue(0x10, &mut unmapped);
+        for (i, slot) in slots.enumerate() {
+            batch.queue(&raw const values[1000 + i] as usize, slot);
+        }
+
+        let results = batch.execute();
+        assert_eq!(results.len(), 2001);
+        assert!(results[bad].is_err());
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2000);
+        assert_eq!(out, values);
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::pod::{from_bytes, Pod};
+use crate::remote_ptr::RemoteSlice;
+
+const POINTER_SIZE: usize = std::mem::size_of::<usize>();
+/// Inline buffer of an MSVC `std::string`, 16 bytes at any pointer width.
+const MSVC_SSO_SIZE: usize = 16;
+
+/// C++ standard library whose container layouts to decode. Layouts are those of release
+/// builds for a target with the same pointer width as this process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StdLib {
+    /// GCC's libstdc++, new (C++11) string ABI.
+    LibStdCxx,
+    /// LLVM's libc++, default (non-alternate) string layout, little endian.
+    LibCxx,
+    /// Microsoft's STL.
+    Msvc,
+}
+
+/// Guards against corrupt or freed containers. Anything over a limit, and any link
+/// structure that disagrees with the container's stored size, is `CorruptContainer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerOptions {
+    /// Most elements a vector, list, map or unordered map may hold.
+    pub max_elements: usize,
+    /// Longest string, in bytes.
+    pub max_string_len: usize,
+}
+
+impl Default for ContainerOptions {
+    fn default() -> Self {
+        Self {
+            max_elements: 1 << 20,
+            max_string_len: 1 << 20,
+        }
+    }
+}
+
+/// Reads standard containers, given the address of the container object itself
+/// (not of its heap storage). Elements must be `Pod`.
+#[derive(Debug)]
+pub struct StdReader<'m, 'a, B: ?Sized> {
+    mem: &'m mmg<'a, B>,
+    lib: StdLib,
+    options: ContainerOptions,
+}
+
+/// Where the links of a tree node live and what terminates a branch.
+struct TreeLayout {
+    root: usize,
+    size: usize,
+    nil: usize,
+    left: usize,
+    right: usize,
+    /// End of the node's link fields, where the value starts once aligned.
+    header: usize,
+}
+
+impl<'m, 'a, B: MemoryBackend + ?Sized> StdReader<'m, 'a, B> {
+    #[must_use]
+    pub fn new(mem: &'m mmg<'a, B>, lib: StdLib) -> Self {
+        Self {
+            mem,
+            lib,
+            options: ContainerOptions::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_options(mut self, options: ContainerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn lib(&self) -> StdLib {
+        self.lib
+    }
+
+    #[inline]
+    fn pointer(&self, address: usize) -> Result<usize> {
+        self.mem.read::<usize>(address)
+    }
+
+    fn check_len(&self, address: usize, len: usize) -> Result<()> {
+        if len > self.options.max_elements {
+            return Err(corrupt(address, "more elements than max_elements"));
+        }
+
+        Ok(())
+    }
+
+    /// `std::vector<T>`: begin, end and end-of-storage pointers in every library.
+    pub fn vector<T: Pod>(&self, address: usize) -> Result<Vec<T>> {
+        let [begin, end, capacity] = self.mem.read::<[usize; 3]>(address)?;
+        let size = std::mem::size_of::<T>();
+
+        if begin > end || end > capacity {
+            return Err(corrupt(address, "begin, end and capacity out of order"));
+        }
+        if size == 0 || begin == end {
+            return Ok(Vec::new());
+        }
+        if (end - begin) % size != 0 {
+            return Err(corrupt(address, "length is not a multiple of the element size"));
+        }
+
+        let len = (end - begin) / size;
+        self.check_len(address, len)?;
+        RemoteSlice::new(begin, len).read(self.mem)
+    }
+
+    /// Raw bytes of a `std::string`, from the inline buffer or the heap.
+    pub fn string_bytes(&self, address: usize) -> Result<Vec<u8>> {
+        let (data, len) = match self.lib {
+            StdLib::LibStdCxx => {
+                let [data, len] = self.mem.read::<[usize; 2]>(address)?;
+                (data, len)
+            }
+            StdLib::LibCxx => {
+                let [cap, len, data] = self.mem.read::<[usize; 3]>(address)?;
+                if cap & 1 == 0 {
+                    let short_len = (cap & 0xFF) >> 1;
+                    if short_len >= 3 * POINTER_SIZE - 1 {
+                        return Err(corrupt(address, "short string longer than its buffer"));
+                    }
+                    (address + 1, short_len)
+                } else {
+                    (data, len)
+                }
+            }
+            StdLib::Msvc => {
+                let [len, capacity] = self.mem.read::<[usize; 2]>(address + MSVC_SSO_SIZE)?;
+                if len > capacity {
+                    return Err(corrupt(address, "length over capacity"));
+                }
+                if capacity < MSVC_SSO_SIZE {
+                    (address, len)
+                } else {
+                    (self.pointer(address)?, len)
+                }
+            }
+        };
+
+        if len > self.options.max_string_len {
+            return Err(corrupt(address, "string longer than max_string_len"));
+        }
+
+        RemoteSlice::<u8>::new(data, len).read(self.mem)
+    }
+
+    /// `std::string`, with invalid UTF-8 replaced.
+    pub fn string(&self, address: usize) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.string_bytes(address)?).into_owned())
+    }
+
+    /// `std::list<T>`, in order.
+    pub fn list<T: Pod>(&self, address: usize) -> Result<Vec<T>> {
+        let value_offset = (2 * POINTER_SIZE).next_multiple_of(std::mem::align_of::<T>());
+
+        let (sentinel, first, next_offset, size) = match self.lib {
+            StdLib::LibStdCxx => (address, self.pointer(address)?, 0, self.pointer(address + 2 * POINTER_SIZE)?),
+            StdLib::LibCxx => (
+                address,
+                self.pointer(address + POINTER_SIZE)?,
+                POINTER_SIZE,
+                self.pointer(address + 2 * POINTER_SIZE)?,
+            ),
+            StdLib::Msvc => {
+                let head = self.pointer(address)?;
+                (head, self.pointer(head)?, 0, self.pointer(address + POINTER_SIZE)?)
+            }
+        };
+
+        self.linked_nodes(address, first, sentinel, next_offset, size)?
+            .into_iter()
+            .map(|node| self.mem.read::<T>(node + value_offset))
+            .collect()
+    }
+
+    /// `std::map<K, V>`, read by an in-order walk of the red-black tree.
+    pub fn map<K: Pod + Ord, V: Pod>(&self, address: usize) -> Result<BTreeMap<K, V>> {
+        let layout = match self.lib {
+            // comparator padded to a word, then the header node (color, parent, left, right)
+            StdLib::LibStdCxx => TreeLayout {
+                root: self.pointer(address + 2 * POINTER_SIZE)?,
+                size: self.pointer(address + 5 * POINTER_SIZE)?,
+                nil: 0,
+                left: 2 * POINTER_SIZE,
+                right: 3 * POINTER_SIZE,
+                header: 4 * POINTER_SIZE,
+            },
+            // nodes: left, right, parent, then a one-byte is_black
+            StdLib::LibCxx => TreeLayout {
+                root: self.pointer(address + POINTER_SIZE)?,
+                size: self.pointer(address + 2 * POINTER_SIZE)?,
+                nil: 0,
+                left: 0,
+                right: POINTER_SIZE,
+                header: 3 * POINTER_SIZE + 1,
+            },
+            StdLib::Msvc => {
+                let head = self.pointer(address)?;
+                // nodes: left, parent, right, then one-byte color and isnil
+                TreeLayout {
+                    root: self.pointer(head + POINTER_SIZE)?,
+                    size: self.pointer(address + POINTER_SIZE)?,
+                    nil: head,
+                    left: 0,
+                    right: 2 * POINTER_SIZE,
+                    header: 3 * POINTER_SIZE + 2,
+                }
+            }
+        };
+        self.check_len(address, layout.size)?;
+
+        let value_offset = layout.header.next_multiple_of(pair_align::<K, V>());
+        let mut entries = BTreeMap::new();
+        let mut stack = Vec::new();
+        let mut node = layout.root;
+        let mut visited = 0;
+
+        loop {
+            while node != 0 && node != layout.nil {
+                if stack.len() >= layout.size {
+                    return Err(corrupt(address, "tree deeper than its size, cycle?"));
+                }
+                stack.push(node);
+                node = self.pointer(node + layout.left)?;
+            }
+
+            let Some(top) = stack.pop() else { break };
+            visited += 1;
+            if visited > layout.size {
+                return Err(corrupt(address, "more nodes than the stored size, cycle?"));
+            }
+
+            let (key, value) = self.pair::<K, V>(top + value_offset)?;
+            entries.insert(key, value);
+            node = self.pointer(top + layout.right)?;
+        }
+
+        if visited != layout.size {
+            return Err(corrupt(address, "fewer nodes than the stored size"));
+        }
+
+        Ok(entries)
+    }
+
+    /// `std::unordered_map<K, V>`, read by following the node list rather than the buckets.
+    pub fn unordered_map<K: Pod + Eq + Hash, V: Pod>(&self, address: usize) -> Result<HashMap<K, V>> {
+        let align = pair_align::<K, V>();
+
+        let (nodes, value_offset) = match self.lib {
+            StdLib::LibStdCxx => {
+                let [first, size] = self.mem.read::<[usize; 2]>(address + 2 * POINTER_SIZE)?;
+                (self.linked_nodes(address, first, 0, 0, size)?, POINTER_SIZE.next_multiple_of(align))
+            }
+            StdLib::LibCxx => {
+                let [first, size] = self.mem.read::<[usize; 2]>(address + 2 * POINTER_SIZE)?;
+                let nodes = self.linked_nodes(address, first, 0, 0, size)?;
+                (nodes, (2 * POINTER_SIZE).next_multiple_of(align))
+            }
+            StdLib::Msvc => {
+                let [head, size] = self.mem.read::<[usize; 2]>(address + POINTER_SIZE)?;
+                let nodes = self.linked_nodes(address, self.pointer(head)?, head, 0, size)?;
+                (nodes, (2 * POINTER_SIZE).next_multiple_of(align))
+            }
+        };
+
+        nodes.into_iter().map(|node| self.pair::<K, V>(node + value_offset)).collect()
+    }
+
+    /// Follows `next` links from `first` until `end`, insisting on exactly `size` nodes.
+    fn linked_nodes(&self, address: usize, first: usize, end: usize, next_offset: usize, size: usize) -> Result<Vec<usize>> {
+        self.check_len(address, size)?;
+
+        let mut nodes = Vec::with_capacity(size);
+        let mut node = first;
+
+        while node != end {
+            if nodes.len() == size {
+                return Err(corrupt(address, "more nodes than the stored size, cycle?"));
+            }
+            if node == 0 {
+                return Err(corrupt(address, "null link"));
+            }
+
+            nodes.push(node);
+            node = self.pointer(node + next_offset)?;
+        }
+
+        if nodes.len() != size {
+            return Err(corrupt(address, "fewer nodes than the stored size"));
+        }
+
+        Ok(nodes)
+    }
+
+    /// A `std::pair<const K, V>` at `address`.
+    fn pair<K: Pod, V: Pod>(&self, address: usize) -> Result<(K, V)> {
+        let value_offset = std::mem::size_of::<K>().next_multiple_of(std::mem::align_of::<V>());
+        let bytes = RemoteSlice::<u8>::new(address, value_offset + std::mem::size_of::<V>()).read(self.mem)?;
+
+        match (from_bytes::<K>(&bytes), from_bytes::<V>(&bytes[value_offset..])) {
+            (Some(key), Some(value)) => Ok((key, value)),
+            _ => unreachable!("pair bytes cover both members"),
+        }
+    }
+}
+
+const fn pair_align<K, V>() -> usize {
+    let (key, value) = (std::mem::align_of::<K>(), std::mem::align_of::<V>());
+    if key > value { key } else { value }
+}
+
+fn corrupt(address: usize, reason: &'static str) -> mm_error {
+    mm_error::CorruptContainer { address, reason }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// See `StdReader`.
+    #[must_use]
+    #[inline]
+    pub fn std_reader(&self, lib: StdLib) -> StdReader<'_, 'a, B> {
+        StdReader::new(self, lib)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    const LIBS: [StdLib; 3] = [StdLib::LibStdCxx, StdLib::LibCxx, StdLib::Msvc];
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x4000, Protection::READ_WRITE);
+        mock
+    }
+
+    fn put(mem: &mmg<'_, MockProcess>, address: usize, words: &[usize]) {
+        for (index, word) in words.iter().enumerate() {
+            mem.write(address + index * POINTER_SIZE, word).unwrap();
+        }
+    }
+
+    #[test]
+    fn vectors_and_their_guards() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        mem.write::<[u32; 3]>(0x2000, &[7, 8, 9]).unwrap();
+        put(&mem, 0x1000, &[0x2000, 0x200C, 0x2010]);
+
+        for lib in LIBS {
+            assert_eq!(mem.std_reader(lib).vector::<u32>(0x1000).unwrap(), [7, 8, 9]);
+        }
+
+        let reader = mem.std_reader(StdLib::Msvc);
+        assert!(matches!(reader.vector::<u64>(0x1000), Err(mm_error::CorruptContainer { .. })));
+        put(&mem, 0x1000, &[0x200C, 0x2000, 0x2010]);
+        assert!(matches!(reader.vector::<u32>(0x1000), Err(mm_error::CorruptContainer { .. })));
+
+        put(&mem, 0x1000, &[0x2000, 0x200C, 0x2010]);
+        let tight = ContainerOptions {
+            max_elements: 2,
+            ..ContainerOptions::default()
+        };
+        assert!(reader.with_options(tight).vector::<u32>(0x1000).is_err());
+    }
+
+    #[test]
+    fn strings_inline_and_on_the_heap() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        let long = "a string well past any inline buffer";
+        mem.write_bytes(0x3000, long.as_bytes()).unwrap();
+
+        // libstdc++: pointer, length, 16-byte local buffer
+        put(&mem, 0x1000, &[0x1010, 5]);
+        mem.write_bytes(0x1010, b"short").unwrap();
+        put(&mem, 0x1100, &[0x3000, long.len(), 64]);
+        let gnu = mem.std_reader(StdLib::LibStdCxx);
+        assert_eq!(gnu.string(0x1000).unwrap(), "short");
+        assert_eq!(gnu.string(0x1100).unwrap(), long);
+
+        // libc++: low bit of the first byte tells long from short
+        mem.write_bytes(0x1200, &[5 << 1]).unwrap();
+        mem.write_bytes(0x1201, b"short").unwrap();
+        put(&mem, 0x1300, &[64 | 1, long.len(), 0x3000]);
+        let llvm = mem.std_reader(StdLib::LibCxx);
+        assert_eq!(llvm.string(0x1200).unwrap(), "short");
+        assert_eq!(llvm.string(0x1300).unwrap(), long);
+
+        // MSVC: 16-byte buffer or pointer, then size and capacity
+        mem.write_bytes(0x1400, b"short").unwrap();
+        put(&mem, 0x1400 + MSVC_SSO_SIZE, &[5, 15]);
+        put(&mem, 0x1500, &[0x3000]);
+        put(&mem, 0x1500 + MSVC_SSO_SIZE, &[long.len(), 47]);
+        let msvc = mem.std_reader(StdLib::Msvc);
+        assert_eq!(msvc.string(0x1400).unwrap(), "short");
+        assert_eq!(msvc.string(0x1500).unwrap(), long);
+
+        put(&mem, 0x1500 + MSVC_SSO_SIZE, &[usize::MAX, usize::MAX]);
+        assert!(matches!(msvc.string(0x1500), Err(mm_error::CorruptContainer { .. })));
+    }
+
+    #[test]
+    fn lists_stop_on_cycles() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        // nodes at 0x2000, 0x2100, 0x2200; value after two links
+        for (index, node) in [0x2000, 0x2100, 0x2200].into_iter().enumerate() {
+            mem.write::<u32>(node + 2 * POINTER_SIZE, &(index as u32 * 10)).unwrap();
+        }
+
+        // libstdc++: next, prev, size; sentinel is the list itself
+        put(&mem, 0x1000, &[0x2000, 0x2200, 3]);
+        put(&mem, 0x2000, &[0x2100]);
+        put(&mem, 0x2100, &[0x2200]);
+        put(&mem, 0x2200, &[0x1000]);
+        let gnu = mem.std_reader(StdLib::LibStdCxx);
+        assert_eq!(gnu.list::<u32>(0x1000).unwrap(), [0, 10, 20]);
+
+        put(&mem, 0x2200, &[0x2000]);
+        assert!(matches!(gnu.list::<u32>(0x1000), Err(mm_error::CorruptContainer { .. })));
+
+        // libc++: prev, next, size
+        put(&mem, 0x1100, &[0x2200, 0x2000, 3]);
+        for (node, next) in [(0x2000, 0x2100), (0x2100, 0x2200), (0x2200, 0x1100)] {
+            put(&mem, node + POINTER_SIZE, &[next]);
+        }
+        assert_eq!(mem.std_reader(StdLib::LibCxx).list::<u32>(0x1100).unwrap(), [0, 10, 20]);
+
+        // MSVC: head pointer and size; the head node is the sentinel
+        put(&mem, 0x1200, &[0x2300, 3]);
+        put(&mem, 0x2300, &[0x2000]);
+        for (node, next) in [(0x2000, 0x2100), (0x2100, 0x2200), (0x2200, 0x2300)] {
+            put(&mem, node, &[next]);
+        }
+        assert_eq!(mem.std_reader(StdLib::Msvc).list::<u32>(0x1200).unwrap(), [0, 10, 20]);
+    }
+
+    #[test]
+    fn maps_walk_the_tree_in_order() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        let (root, low, high) = (0x2000, 0x2100, 0x2200);
+
+        // libstdc++: header at word 1 (color, parent, left, right), count at word 5;
+        // node links at words 1/2/3, value at word 4
+        put(&mem, 0x1000, &[0, 0, root, low, high, 3]);
+        for (node, left, right, key) in [(root, low, high, 2u32), (low, 0, 0, 1), (high, 0, 0, 3)] {
+            put(&mem, node + 2 * POINTER_SIZE, &[left, right]);
+            mem.write::<[u32; 2]>(node + 4 * POINTER_SIZE, &[key, key * 100]).unwrap();
+        }
+        let map = mem.std_reader(StdLib::LibStdCxx).map::<u32, u32>(0x1000).unwrap();
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 100), (2, 200), (3, 300)]);
+
+        // libc++: begin node, root, size; node left/right/parent/color, then the value
+        put(&mem, 0x1100, &[low, root, 3]);
+        for (node, left, right, key) in [(root, low, high, 2u32), (low, 0, 0, 1), (high, 0, 0, 3)] {
+            put(&mem, node, &[left, right]);
+            mem.write::<[u32; 2]>(node + (3 * POINTER_SIZE + 1).next_multiple_of(4), &[key, key * 100]).unwrap();
+        }
+        let map = mem.std_reader(StdLib::LibCxx).map::<u32, u32>(0x1100).unwrap();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+        // MSVC: head, size; leaves point back at the head
+        let head = 0x2300;
+        put(&mem, 0x1200, &[head, 3]);
+        put(&mem, head, &[low, root, high]);
+        for (node, left, right, key) in [(root, low, high, 2usize), (low, head, head, 1), (high, head, head, 3)] {
+            put(&mem, node, &[left, head, right]);
+            put(&mem, node + (3 * POINTER_SIZE + 2).next_multiple_of(POINTER_SIZE), &[key, key * 100]);
+        }
+        let msvc = mem.std_reader(StdLib::Msvc);
+        assert_eq!(msvc.map::<usize, usize>(0x1200).unwrap()[&3], 300);
+
+        put(&mem, low, &[root]);
+        assert!(matches!(msvc.map::<usize, usize>(0x1200), Err(mm_error::CorruptContainer { .. })));
+    }
+
+    #[test]
+    fn unordered_maps_follow_the_node_list() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        // libstdc++: before-begin at word 2, count at word 3; value after the next link
+        put(&mem, 0x1000, &[0, 0, 0x2000, 2]);
+        put(&mem, 0x2000, &[0x2100, 1, 10]);
+        put(&mem, 0x2100, &[0, 2, 20]);
+        let map = mem.std_reader(StdLib::LibStdCxx).unordered_map::<usize, usize>(0x1000).unwrap();
+        assert_eq!(map, HashMap::from([(1, 10), (2, 20)]));
+
+        // libc++: first node at word 2, size at word 3; next, hash, then value
+        put(&mem, 0x1100, &[0, 0, 0x2200, 2]);
+        put(&mem, 0x2200, &[0x2300, 0xAAAA, 1, 10]);
+        put(&mem, 0x2300, &[0, 0xBBBB, 2, 20]);
+        let map = mem.std_reader(StdLib::LibCxx).unordered_map::<usize, usize>(0x1100).unwrap();
+        assert_eq!(map, HashMap::from([(1, 10), (2, 20)]));
+
+        // MSVC: the list of all elements sits at word 1
+        put(&mem, 0x1200, &[0, 0x2400, 2]);
+        put(&mem, 0x2400, &[0x2500]);
+        put(&mem, 0x2500, &[0x2600, 0x2400, 1, 10]);
+        put(&mem, 0x2600, &[0x2400, 0x2500, 2, 20]);
+        let map = mem.std_reader(StdLib::Msvc).unordered_map::<usize, usize>(0x1200).unwrap();
+        assert_eq!(map, HashMap::from([(1, 10), (2, 20)]));
+
+        put(&mem, 0x1200 + POINTER_SIZE, &[0x2400, 1]);
+        assert!(mem.std_reader(StdLib::Msvc).unordered_map::<usize, usize>(0x1200).is_err());
+    }
+}
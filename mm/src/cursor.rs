@@ -0,0 +1,340 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+use crate::backend::MemoryBackend;
+use crate::error::mm_error;
+use crate::memory::mmg;
+use crate::pattern::PAGE_SIZE;
+
+/// `Read`, `BufRead`, `Seek` and `Write` over `[base, base + len)` of the target, for
+/// handing remote memory to parsers that take a stream.
+///
+/// Reads go through a buffer of `capacity` bytes (a page by default); reads at least that
+/// large bypass it. Writes are collected while they are contiguous and sent on `flush`,
+/// before a write that would overflow the buffer, before the next read, and on drop (where
+/// errors are lost, so flush explicitly). Positions are relative to `base`; reading or writing at or past
+/// `len` behaves like the end of a file. Backend errors come back as `io::Error`s
+/// wrapping the `mm_error`.
+#[derive(Debug)]
+pub struct RemoteCursor<'m, 'a, B: MemoryBackend + ?Sized> {
+    mem: &'m mmg<'a, B>,
+    base: usize,
+    len: usize,
+    position: usize,
+    capacity: usize,
+    /// Cached bytes starting at `buffer_start`.
+    buffer: Vec<u8>,
+    buffer_start: usize,
+    /// Unflushed writes starting at `pending_start`.
+    pending: Vec<u8>,
+    pending_start: usize,
+}
+
+impl<'m, 'a, B: MemoryBackend + ?Sized> RemoteCursor<'m, 'a, B> {
+    #[must_use]
+    pub fn new(mem: &'m mmg<'a, B>, base: usize, len: usize) -> Self {
+        Self {
+            mem,
+            base,
+            len,
+            position: 0,
+            capacity: PAGE_SIZE,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            pending: Vec::new(),
+            pending_start: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Offset from `base`.
+    #[must_use]
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Target address of the current position.
+    #[must_use]
+    #[inline]
+    pub const fn address(&self) -> usize {
+        self.base.wrapping_add(self.position)
+    }
+
+    #[inline]
+    const fn remaining(&self) -> usize {
+        self.len.saturating_sub(self.position)
+    }
+
+    fn flush_pending(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        // Like `BufWriter`, only drop what reached the target so a retry resumes at the tail.
+        let address = self.base.wrapping_add(self.pending_start);
+        let expected = self.pending.len();
+        let written = self.mem.write_bytes(address, &self.pending).map_err(io_error)?;
+        self.pending.drain(..written.min(expected));
+        self.pending_start += written;
+
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                mm_error::InvalidBufferSize { expected, actual: written },
+            ))
+        }
+    }
+
+    /// Reads straight into `out` at the current position, without touching the buffer.
+    fn read_direct(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        self.flush_pending()?;
+        let len = out.len().min(self.remaining());
+        let read = self.mem.read_bytes(self.address(), &mut out[..len]).map_err(io_error)?;
+        self.position += read;
+        Ok(read)
+    }
+}
+
+fn io_error(error: mm_error) -> io::Error {
+    io::Error::other(error)
+}
+
+impl<B: MemoryBackend + ?Sized> Read for RemoteCursor<'_, '_, B> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let buffered = self.position >= self.buffer_start && self.position < self.buffer_start + self.buffer.len();
+        if !buffered && out.len() >= self.capacity {
+            return self.read_direct(out);
+        }
+
+        let available = self.fill_buf()?;
+        let read = available.len().min(out.len());
+        out[..read].copy_from_slice(&available[..read]);
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl<B: MemoryBackend + ?Sized> BufRead for RemoteCursor<'_, '_, B> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let end = self.buffer_start + self.buffer.len();
+        if self.position < self.buffer_start || self.position >= end {
+            self.flush_pending()?;
+            let len = self.capacity.min(self.remaining());
+
+            self.buffer.resize(len, 0);
+            self.buffer_start = self.position;
+            let read = match self.mem.read_bytes(self.address(), &mut self.buffer) {
+                Ok(read) => read,
+                Err(error) => {
+                    self.buffer.clear();
+                    return Err(io_error(error));
+                }
+            };
+            self.buffer.truncate(read);
+        }
+
+        Ok(&self.buffer[self.position - self.buffer_start..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+impl<B: MemoryBackend + ?Sized> Write for RemoteCursor<'_, '_, B> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let len = data.len().min(self.remaining());
+        if len == 0 {
+            return Ok(0);
+        }
+
+        // Whatever was cached may now be stale.
+        self.buffer.clear();
+
+        // Flush before taking `data`, never after, so an error means none of it was taken.
+        let contiguous = self.pending_start + self.pending.len() == self.position;
+        if !self.pending.is_empty() && (!contiguous || self.pending.len() + len > self.capacity) {
+            self.flush_pending()?;
+        }
+        if self.pending.is_empty() && len >= self.capacity {
+            let written = self.mem.write_bytes(self.address(), &data[..len]).map_err(io_error)?;
+            self.position += written;
+            return Ok(written);
+        }
+
+        if self.pending.is_empty() {
+            self.pending_start = self.position;
+        }
+        self.pending.extend_from_slice(&data[..len]);
+        self.position += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_pending()
+    }
+}
+
+impl<B: MemoryBackend + ?Sized> Seek for RemoteCursor<'_, '_, B> {
+    fn seek(&mut self, from: SeekFrom) -> io::Result<u64> {
+        let (origin, offset) = match from {
+            SeekFrom::Start(offset) => (0, i128::from(offset)),
+            SeekFrom::End(offset) => (self.len, i128::from(offset)),
+            SeekFrom::Current(offset) => (self.position, i128::from(offset)),
+        };
+
+        let position = usize::try_from(origin as i128 + offset)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative or overflowing position"))?;
+        self.position = position;
+        Ok(position as u64)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position as u64)
+    }
+}
+
+impl<B: MemoryBackend + ?Sized> Drop for RemoteCursor<'_, '_, B> {
+    fn drop(&mut self) {
+        let _ = self.flush_pending();
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// See `RemoteCursor`.
+    #[must_use]
+    #[inline]
+    pub fn cursor(&self, address: usize, len: usize) -> RemoteCursor<'_, 'a, B> {
+        RemoteCursor::new(self, address, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::{MockFault, MockProcess};
+    use crate::region::Protection;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x3000, Protection::READ_WRITE);
+        mock
+    }
+
+    #[test]
+    fn buffered_lines_and_seeks() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        mem.write_bytes(0x1000, b"first\nsecond\nthird").unwrap();
+
+        let mut cursor = mem.cursor(0x1000, 18).with_capacity(8);
+        mock.clear_accesses();
+        let lines: Vec<_> = (&mut cursor).lines().map(|line| line.unwrap()).collect();
+        assert_eq!(lines, ["first", "second", "third"]);
+        let reads: Vec<_> = mock.reads().iter().map(|r| (r.address, r.len)).collect();
+        assert_eq!(reads, [(0x1000, 8), (0x1008, 8), (0x1010, 2)]);
+
+        assert_eq!(cursor.seek(SeekFrom::End(-5)).unwrap(), 13);
+        let mut word = String::new();
+        cursor.read_to_string(&mut word).unwrap();
+        assert_eq!(word, "third");
+        assert_eq!(cursor.read(&mut [0; 4]).unwrap(), 0);
+
+        cursor.seek(SeekFrom::Start(6)).unwrap();
+        assert_eq!(cursor.seek(SeekFrom::Current(-1)).unwrap(), 5);
+        assert_eq!(cursor.address(), 0x1005);
+        assert!(cursor.seek(SeekFrom::Current(-6)).is_err());
+
+        let mut large = [0u8; 16];
+        mock.clear_accesses();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        cursor.read_exact(&mut large).unwrap();
+        assert_eq!(&large, b"first\nsecond\nthi");
+        assert_eq!(mock.reads().len(), 1);
+    }
+
+    #[test]
+    fn writes_are_collected_until_flushed() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let mut cursor = mem.cursor(0x1000, 0x20).with_capacity(16);
+        mock.clear_accesses();
+        cursor.write_all(b"abc").unwrap();
+        cursor.write_all(b"def").unwrap();
+        assert!(mock.writes().is_empty());
+
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let mut back = [0u8; 6];
+        cursor.read_exact(&mut back).unwrap();
+        assert_eq!(&back, b"abcdef");
+        let writes: Vec<_> = mock.writes().iter().map(|w| (w.address, w.len)).collect();
+        assert_eq!(writes, [(0x1000, 6)]);
+
+        cursor.seek(SeekFrom::Start(0x1E)).unwrap();
+        assert_eq!(cursor.write(b"xyz").unwrap(), 2);
+        assert_eq!(cursor.write_all(b"!").unwrap_err().kind(), io::ErrorKind::WriteZero);
+        drop(cursor);
+        assert_eq!(mem.read_bytes_vec(0x101E, 3).unwrap(), b"xy\0");
+        assert_eq!(mock.writes().last().map(|w| (w.address, w.len)), Some((0x101E, 2)));
+    }
+
+    #[test]
+    fn faults_become_io_errors() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let mut cursor = mem.cursor(0x3FF8, 0x10);
+        let mut bytes = Vec::new();
+        let error = cursor.read_to_end(&mut bytes).unwrap_err();
+        assert_eq!(bytes.len(), 8);
+        assert!(error.get_ref().is_some_and(|inner| inner.is::<mm_error>()));
+
+        cursor.seek(SeekFrom::Start(8)).unwrap();
+        cursor.write_all(&[1; 4]).unwrap();
+        assert!(cursor.flush().is_err());
+        assert!(cursor.flush().is_err());
+
+        // A write that cannot make room fails without taking its bytes.
+        let mut cursor = mem.cursor(0x3FF0, 0x20).with_capacity(8);
+        cursor.seek(SeekFrom::Start(0x10)).unwrap();
+        assert_eq!(cursor.write(&[1; 6]).unwrap(), 6);
+        assert!(cursor.write(&[2; 6]).is_err());
+        assert_eq!(cursor.position(), 0x16);
+
+        // A short write keeps the tail, which lands once the fault is gone.
+        let mut cursor = mem.cursor(0x1000, 0x10);
+        mock.inject_fault(0x1004..0x1008, MockFault::PartialCopy);
+        cursor.write_all(b"abcdefgh").unwrap();
+        assert_eq!(cursor.flush().unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(mem.read_bytes_vec(0x1000, 8).unwrap(), b"abcd");
+        mock.clear_faults();
+        cursor.flush().unwrap();
+        assert_eq!(mem.read_bytes_vec(0x1000, 8).unwrap(), b"abcdefgh");
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::backend::MemoryBackend;
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::region::Protection;
+
+pub(crate) const ET_DYN: u16 = 3;
+pub(crate) const ET_CORE: u16 = 4;
+
+pub(crate) const PT_LOAD: u32 = 1;
+pub(crate) const PT_NOTE: u32 = 4;
+
+pub(crate) const PF_X: u32 = 1;
+pub(crate) const PF_W: u32 = 2;
+pub(crate) const PF_R: u32 = 4;
+
+pub(crate) const NT_FILE: u32 = 0x4649_4C45;
+
+const ELF_MAGIC: [u8; 4] = [0x7F, b'E', b'L', b'F'];
+const ELFCLASS64: u8 = 2;
+const ELFDATA2MSB: u8 = 2;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ElfHeader {
+    pub is_64: bool,
+    pub big_endian: bool,
+    pub e_type: u16,
+    pub entry: u64,
+    pub phoff: u64,
+    pub phentsize: u16,
+    pub phnum: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProgramHeader {
+    pub p_type: u32,
+    pub p_flags: u32,
+    pub p_offset: u64,
+    pub p_vaddr: u64,
+    pub p_filesz: u64,
+    pub p_memsz: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Note<'a> {
+    pub name: &'a [u8],
+    pub n_type: u32,
+    pub desc: &'a [u8],
+}
+
+/// One file-backed mapping, as listed by `/proc/<pid>/maps` or an `NT_FILE` note.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FileMapping<'a> {
+    pub path: &'a str,
+    pub start: usize,
+    pub end: usize,
+    pub file_offset: u64,
+    pub readable: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub const fn new(bytes: &'a [u8], big_endian: bool) -> Self {
+        Self { bytes, big_endian }
+    }
+
+    fn array<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.bytes.get(offset..offset.checked_add(N)?)?.try_into().ok()
+    }
+
+    pub fn u16(&self, offset: usize) -> Option<u16> {
+        let raw = self.array::<2>(offset)?;
+        Some(if self.big_endian { u16::from_be_bytes(raw) } else { u16::from_le_bytes(raw) })
+    }
+
+    pub fn u32(&self, offset: usize) -> Option<u32> {
+        let raw = self.array::<4>(offset)?;
+        Some(if self.big_endian { u32::from_be_bytes(raw) } else { u32::from_le_bytes(raw) })
+    }
+
+    pub fn u64(&self, offset: usize) -> Option<u64> {
+        let raw = self.array::<8>(offset)?;
+        Some(if self.big_endian { u64::from_be_bytes(raw) } else { u64::from_le_bytes(raw) })
+    }
+
+    pub fn word(&self, offset: usize, is_64: bool) -> Option<u64> {
+        if is_64 { self.u64(offset) } else { self.u32(offset).map(u64::from) }
+    }
+}
+
+impl ProgramHeader {
+    pub const fn protection(&self) -> Protection {
+        let mut protection = Protection::NONE;
+        if self.p_flags & PF_R != 0 {
+            protection = protection.union(Protection::READ);
+        }
+        if self.p_flags & PF_W != 0 {
+            protection = protection.union(Protection::WRITE);
+        }
+        if self.p_flags & PF_X != 0 {
+            protection = protection.union(Protection::EXECUTE);
+        }
+        protection
+    }
+}
+
+impl ElfHeader {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.get(..4)? != ELF_MAGIC {
+            return None;
+        }
+
+        let is_64 = *bytes.get(4)? == ELFCLASS64;
+        let big_endian = *bytes.get(5)? == ELFDATA2MSB;
+        let r = Reader::new(bytes, big_endian);
+
+        let (entry, phoff, phentsize, phnum) = if is_64 {
+            (r.u64(0x18)?, r.u64(0x20)?, r.u16(0x36)?, r.u16(0x38)?)
+        } else {
+            (u64::from(r.u32(0x18)?), u64::from(r.u32(0x1C)?), r.u16(0x2A)?, r.u16(0x2C)?)
+        };
+
+        Some(Self {
+            is_64,
+            big_endian,
+            e_type: r.u16(0x10)?,
+            entry,
+            phoff,
+            phentsize,
+            phnum,
+        })
+    }
+
+    #[inline]
+    pub const fn reader<'a>(&self, bytes: &'a [u8]) -> Reader<'a> {
+        Reader::new(bytes, self.big_endian)
+    }
+
+    #[inline]
+    pub fn program_headers_size(&self) -> usize {
+        usize::from(self.phentsize) * usize::from(self.phnum)
+    }
+
+    /// `bytes` must start at the program header table, not at the file header.
+    pub fn program_headers(&self, bytes: &[u8]) -> Vec<ProgramHeader> {
+        let r = self.reader(bytes);
+        let stride = usize::from(self.phentsize);
+
+        (0..usize::from(self.phnum))
+            .map_while(|i| {
+                let at = i * stride;
+                if self.is_64 {
+                    Some(ProgramHeader {
+                        p_type: r.u32(at)?,
+                        p_flags: r.u32(at + 0x04)?,
+                        p_offset: r.u64(at + 0x08)?,
+                        p_vaddr: r.u64(at + 0x10)?,
+                        p_filesz: r.u64(at + 0x20)?,
+                        p_memsz: r.u64(at + 0x28)?,
+                    })
+                } else {
+                    Some(ProgramHeader {
+                        p_type: r.u32(at)?,
+                        p_offset: u64::from(r.u32(at + 0x04)?),
+                        p_vaddr: u64::from(r.u32(at + 0x08)?),
+                        p_filesz: u64::from(r.u32(at + 0x10)?),
+                        p_memsz: u64::from(r.u32(at + 0x14)?),
+                        p_flags: r.u32(at + 0x18)?,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Notes packed in a `PT_NOTE` segment. Linux pads name and desc to 4 bytes
+    /// for both ELF classes.
+    pub fn notes<'a>(&self, bytes: &'a [u8]) -> Vec<Note<'a>> {
+        let r = self.reader(bytes);
+        let align = |n: usize| n.next_multiple_of(4);
+        let mut notes = Vec::new();
+        let mut at = 0;
+
+        while let (Some(namesz), Some(descsz), Some(n_type)) = (r.u32(at), r.u32(at + 4), r.u32(at + 8)) {
+            let name_at = at + 12;
+            let desc_at = name_at + align(namesz as usize);
+
+            let (Some(name), Some(desc)) = (
+                bytes.get(name_at..name_at + namesz as usize),
+                bytes.get(desc_at..desc_at + descsz as usize),
+            ) else {
+                break;
+            };
+
+            let name = name.strip_suffix(&[0]).unwrap_or(name);
+            notes.push(Note { name, n_type, desc });
+            at = desc_at + align(descsz as usize);
+        }
+
+        notes
+    }
+
+    /// Decodes an `NT_FILE` note into one mapping per entry.
+    pub fn file_note<'a>(&self, desc: &'a [u8]) -> Vec<FileMapping<'a>> {
+        let r = self.reader(desc);
+        let word = if self.is_64 { 8 } else { 4 };
+
+        let (Some(count), Some(page_size)) = (r.word(0, self.is_64), r.word(word, self.is_64)) else {
+            return Vec::new();
+        };
+
+        let table_at = word * 2;
+        let names_at = (count as usize).saturating_mul(word * 3).saturating_add(table_at);
+        let mut names = desc.get(names_at..).unwrap_or_default().split(|&b| b == 0);
+
+        (0..count as usize)
+            .map_while(|i| {
+                let at = table_at + i * word * 3;
+                Some(FileMapping {
+                    start: r.word(at, self.is_64)? as usize,
+                    end: r.word(at + word, self.is_64)? as usize,
+                    file_offset: r.word(at + word * 2, self.is_64)?.wrapping_mul(page_size),
+                    path: std::str::from_utf8(names.next()?).ok()?,
+                    readable: true,
+                })
+            })
+            .collect()
+    }
+
+    /// Absolute entry point of an image whose first byte is mapped at `base`.
+    pub fn entry_point(&self, base: usize, program_headers: &[ProgramHeader]) -> usize {
+        if self.e_type != ET_DYN {
+            return self.entry as usize;
+        }
+
+        let first_vaddr = program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PT_LOAD)
+            .map(|ph| ph.p_vaddr)
+            .min()
+            .unwrap_or(0)
+            & !0xFFF;
+
+        base.wrapping_add(self.entry.wrapping_sub(first_vaddr) as usize)
+    }
+}
+
+/// Reads the ELF header mapped at `base` and returns the absolute entry point.
+pub(crate) fn remote_entry_point<B: MemoryBackend + ?Sized>(reader: &mmg<'_, B>, base: usize) -> Option<usize> {
+    let mut header = [0u8; 64];
+    reader.read_bytes(base, &mut header).ok()?;
+    let elf = ElfHeader::parse(&header)?;
+
+    let table = reader
+        .read_bytes_vec(base.wrapping_add(elf.phoff as usize), elf.program_headers_size())
+        .unwrap_or_default();
+
+    Some(elf.entry_point(base, &elf.program_headers(&table)))
+}
+
+/// Groups the mappings of each backing file (by full path, so same-named files from
+/// different directories stay apart) into one `ModuleInfo` spanning all of them, with the
+/// entry point taken from the ELF header at file offset 0.
+pub(crate) fn modules_from_mappings<'m, B: MemoryBackend + ?Sized>(
+    mappings: impl IntoIterator<Item = FileMapping<'m>>,
+    reader: &mmg<'_, B>,
+) -> Vec<ModuleInfo> {
+    let mut result: Vec<ModuleInfo> = Vec::new();
+    let mut header_mapping: Vec<Option<usize>> = Vec::new();
+    let mut by_path: HashMap<&str, usize> = HashMap::new();
+
+    for mapping in mappings {
+        let path = mapping.path.trim_end_matches(" (deleted)");
+
+        let index = *by_path.entry(path).or_insert_with(|| {
+            let name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string());
+            result.push(ModuleInfo::new(name, mapping.start, 0, 0));
+            header_mapping.push(None);
+            result.len() - 1
+        });
+
+        let module = &mut result[index];
+        let end = module.end_address().max(mapping.end);
+        module.addy = module.addy.min(mapping.start);
+        module.size = end - module.addy;
+
+        if mapping.file_offset == 0 && mapping.readable && header_mapping[index].is_none() {
+            header_mapping[index] = Some(mapping.start);
+        }
+    }
+
+    for (module, header_at) in result.iter_mut().zip(header_mapping) {
+        if let Some(base) = header_at {
+            module.entry_point = remote_entry_point(reader, base).unwrap_or(0);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+
+    #[test]
+    fn same_named_files_stay_separate_modules() {
+        let mock = MockProcess::new();
+        let mem = mmg::new(&mock);
+        let mapping = |path, start, end, file_offset| FileMapping {
+            path,
+            start,
+            end,
+            file_offset,
+            readable: true,
+        };
+
+        let modules = modules_from_mappings(
+            [
+                mapping("/opt/a/libfoo.so", 0x10000, 0x11000, 0),
+                mapping("/opt/b/libfoo.so", 0x20000, 0x21000, 0),
+                mapping("/opt/a/libfoo.so", 0x11000, 0x13000, 0x1000),
+                mapping("/opt/b/libfoo.so (deleted)", 0x21000, 0x22000, 0x1000),
+            ],
+            &mem,
+        );
+
+        let spans: Vec<_> = modules.iter().map(|m| (m.name.as_str(), m.addy, m.size)).collect();
+        assert_eq!(spans, [("libfoo.so", 0x10000, 0x3000), ("libfoo.so", 0x20000, 0x2000)]);
+    }
+}
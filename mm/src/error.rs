@@ -1,7 +1,11 @@
 use thiserror::Error;
 
+use crate::memory::PointerHop;
+
 pub type Result<T> = std::result::Result<T, mm_error>;
 
+// the public name predates the lint gate; renaming it would break every caller
+#[allow(non_camel_case_types)]
 #[derive(Error, Debug)]
 pub enum mm_error {
     #[error("mm_error -> failed to open process: {0}")]
@@ -19,15 +23,23 @@ pub enum mm_error {
     #[error("mm_error -> module enumeration failed, ec . {0}")]
     ModuleEnumFailed(u32),
 
-    #[error("mm_error -> mem read failed at -> 0x{address:016X}: ntstatus 0x{status:08X}")]
+    #[cfg_attr(windows, error("mm_error -> mem read failed at -> 0x{address:016X}: ntstatus 0x{status:08X}"))]
+    #[cfg_attr(not(windows), error("mm_error -> mem read failed at -> 0x{address:016X}: errno {status}"))]
     ReadFailed { address: usize, status: i32 },
 
-    #[error("mm_error -> mem write failed at -> 0x{address:016X}: ntstatus 0x{status:08X}")]
+    #[cfg_attr(windows, error("mm_error -> mem write failed at -> 0x{address:016X}: ntstatus 0x{status:08X}"))]
+    #[cfg_attr(not(windows), error("mm_error -> mem write failed at -> 0x{address:016X}: errno {status}"))]
     WriteFailed { address: usize, status: i32 },
 
     #[error("mm_error -> Invalid buffer size : expected {expected}, got {actual}")]
     InvalidBufferSize { expected: usize, actual: usize },
 
+    #[error("mm_error -> invalid {type_name} at 0x{address:016X}")]
+    InvalidValue { address: usize, type_name: &'static str },
+
+    #[error("mm_error -> string at 0x{address:016X} is not valid {encoding}")]
+    StringDecodeFailed { address: usize, encoding: &'static str },
+
     #[error("mm_error -> null ptr encountered")]
     NullPointer,
 
@@ -42,6 +54,39 @@ pub enum mm_error {
 
     #[error("mm_error -> windows api error . {0}")]
     WindowsApiError(u32),
+
+    #[error("mm_error -> failed to load dump . {0}")]
+    DumpLoadFailed(String),
+
+    #[error("mm_error -> corrupt container at 0x{address:016X} . {reason}")]
+    CorruptContainer { address: usize, reason: &'static str },
+
+    #[error("mm_error -> invalid pattern . {0}")]
+    InvalidPattern(String),
+
+    #[error("mm_error -> pointer chain broke at hop {hop}, reading 0x{address:016X} . {source}")]
+    PointerChainBroken {
+        hop: usize,
+        address: usize,
+        source: Box<mm_error>,
+        /// Hops that were read before the failing one.
+        hops: Vec<PointerHop>,
+    },
+
+    #[error("mm_error -> pointer depth exceeded, not following 0x{0:016X}")]
+    PointerDepthExceeded(usize),
+
+    #[error("mm_error -> invalid pointer path . {0}")]
+    InvalidPointerPath(String),
+
+    #[error("mm_error -> invalid scan . {0}")]
+    InvalidScan(&'static str),
+
+    #[error("mm_error -> scan spill file failed . {0}")]
+    ScanSpillFailed(String),
+
+    #[error("mm_error -> operation not supported by backend . {0}")]
+    Unsupported(&'static str),
 }
 
 impl mm_error {
@@ -50,6 +95,7 @@ impl mm_error {
         matches!(self, Self::ReadFailed { .. } | Self::WriteFailed { .. })
     }
 
+    #[cfg(windows)]
     #[must_use]
     pub const fn ntstatus(&self) -> Option<i32> {
         match self {
@@ -58,4 +104,13 @@ impl mm_error {
             _ => None,
         }
     }
-}
\ No newline at end of file
+
+    #[cfg(not(windows))]
+    #[must_use]
+    pub const fn errno(&self) -> Option<i32> {
+        match self {
+            Self::ReadFailed { status, .. } | Self::WriteFailed { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+use crate::backend::MemoryBackend;
+use crate::elf::{modules_from_mappings, FileMapping};
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::procfs::{self, read_maps, MapsEntry};
+use crate::region::MemoryRegion;
+
+/// Iovecs per `process_vm_readv`, the kernel's `IOV_MAX`.
+const IOV_BATCH: usize = 1024;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+pub struct p_handle {
+    pid: libc::pid_t,
+    mem: Option<File>,
+    writable: bool,
+}
+
+impl p_handle {
+    pub fn open_by_pid(pid: u32) -> Result<Self> {
+        let raw_pid = libc::pid_t::try_from(pid)
+            .map_err(|_| mm_error::ProcessOpenFailed(format!("debug-pid -> {} out of range", pid)))?;
+
+        let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+        if !proc_dir.is_dir() {
+            return Err(mm_error::ProcessOpenFailed(format!(
+                "debug-pid -> {} has no /proc entry",
+                pid
+            )));
+        }
+
+        let mem_path = proc_dir.join("mem");
+        let (mem, writable) = match OpenOptions::new().read(true).write(true).open(&mem_path) {
+            Ok(file) => (Some(file), true),
+            Err(_) => (File::open(&mem_path).ok(), false),
+        };
+
+        Ok(Self {
+            pid: raw_pid,
+            mem,
+            writable,
+        })
+    }
+
+    fn process_names(proc_dir: &Path) -> Vec<String> {
+        let mut names = Vec::with_capacity(3);
+
+        if let Ok(comm) = std::fs::read_to_string(proc_dir.join("comm")) {
+            names.push(comm.trim_end_matches('\n').to_string());
+        }
+
+        if let Ok(exe) = std::fs::read_link(proc_dir.join("exe"))
+            && let Some(file_name) = exe.file_name()
+        {
+            names.push(file_name.to_string_lossy().trim_end_matches(" (deleted)").to_string());
+        }
+
+        if let Ok(cmdline) = std::fs::read(proc_dir.join("cmdline")) {
+            let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+            let argv0 = String::from_utf8_lossy(argv0);
+            if let Some(file_name) = Path::new(argv0.as_ref()).file_name() {
+                names.push(file_name.to_string_lossy().into_owned());
+            }
+        }
+
+        names
+    }
+
+    pub fn find_processes_by_name(name: &str) -> Result<Vec<u32>> {
+        let entries = std::fs::read_dir("/proc")
+            .map_err(|e| mm_error::ProcessEnumFailed(e.raw_os_error().unwrap_or(libc::EIO)))?;
+
+        let target_name = name.to_lowercase();
+        let mut matches = Vec::new();
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            if Self::process_names(&entry.path())
+                .iter()
+                .any(|proc_name| proc_name.to_lowercase() == target_name)
+            {
+                matches.push(pid);
+            }
+        }
+
+        matches.sort_unstable();
+        Ok(matches)
+    }
+
+    pub fn find_process_by_name(name: &str) -> Result<u32> {
+        Self::find_processes_by_name(name)?
+            .first()
+            .copied()
+            .ok_or_else(|| mm_error::ProcessNotFound(name.to_string()))
+    }
+
+    fn maps(&self) -> std::io::Result<Vec<MapsEntry>> {
+        read_maps(&self.pid.to_string())
+    }
+
+    pub fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        let maps = self
+            .maps()
+            .map_err(|e| mm_error::ModuleEnumFailed(e.raw_os_error().unwrap_or(libc::EIO) as u32))?;
+
+        let mappings = maps.iter().filter_map(|entry| {
+            Some(FileMapping {
+                path: entry.backing_file()?,
+                start: entry.start,
+                end: entry.end,
+                file_offset: entry.offset,
+                readable: entry.protection().is_readable(),
+            })
+        });
+
+        Ok(modules_from_mappings(mappings, &mmg::new(self)))
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn as_raw(&self) -> libc::pid_t {
+        self.pid
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn pid(&self) -> u32 {
+        self.pid as u32
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        // EPERM: alive, but owned by another user
+        self.pid > 0 && (unsafe { libc::kill(self.pid, 0) } == 0 || last_errno() == libc::EPERM)
+    }
+
+    fn mem_read(&self, address: usize, buffer: &mut [u8], errno: i32) -> Result<usize> {
+        let Some(mem) = &self.mem else {
+            return Err(mm_error::ReadFailed { address, status: errno });
+        };
+
+        mem.read_at(buffer, address as u64).map_err(|e| mm_error::ReadFailed {
+            address,
+            status: e.raw_os_error().unwrap_or(errno),
+        })
+    }
+
+    fn mem_write(&self, address: usize, buffer: &[u8], errno: i32) -> Result<usize> {
+        let Some(mem) = self.mem.as_ref().filter(|_| self.writable) else {
+            return Err(mm_error::WriteFailed { address, status: errno });
+        };
+
+        mem.write_at(buffer, address as u64).map_err(|e| mm_error::WriteFailed {
+            address,
+            status: e.raw_os_error().unwrap_or(errno),
+        })
+    }
+}
+
+#[inline]
+fn last_errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO)
+}
+
+impl MemoryBackend for p_handle {
+    fn read_memory(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+        let local = libc::iovec {
+            iov_base: buffer.as_mut_ptr().cast(),
+            iov_len: buffer.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+
+        let copied = unsafe { libc::process_vm_readv(self.pid, &local, 1, &remote, 1, 0) };
+        if copied >= 0 {
+            return Ok(copied as usize);
+        }
+
+        // /proc/<pid>/mem only when the syscall itself is unavailable
+        match last_errno() {
+            libc::ENOSYS => self.mem_read(address, buffer, libc::ENOSYS),
+            errno => Err(mm_error::ReadFailed { address, status: errno }),
+        }
+    }
+
+    /// One `process_vm_readv` per `IOV_BATCH` requests. The syscall stops at the first
+    /// remote iovec it cannot read, so that request is retried alone (for its exact
+    /// error or short count) and the batch resumes right after it.
+    fn read_scatter(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize>> {
+        let mut results = Vec::with_capacity(requests.len());
+        let mut next = 0;
+
+        while next < requests.len() {
+            let end = (next + IOV_BATCH).min(requests.len());
+            let batch = &mut requests[next..end];
+            let local: Vec<libc::iovec> = batch
+                .iter_mut()
+                .map(|(_, buffer)| libc::iovec {
+                    iov_base: buffer.as_mut_ptr().cast(),
+                    iov_len: buffer.len(),
+                })
+                .collect();
+            let remote: Vec<libc::iovec> = batch
+                .iter()
+                .map(|(address, buffer)| libc::iovec {
+                    iov_base: *address as *mut libc::c_void,
+                    iov_len: buffer.len(),
+                })
+                .collect();
+
+            let count = local.len() as libc::c_ulong;
+            let copied = unsafe { libc::process_vm_readv(self.pid, local.as_ptr(), count, remote.as_ptr(), count, 0) };
+
+            if copied < 0 && last_errno() == libc::ENOSYS {
+                for (address, buffer) in &mut requests[next..end] {
+                    results.push(self.read_memory(*address, buffer));
+                }
+                next = end;
+                continue;
+            }
+
+            let mut remaining = copied.max(0) as usize;
+            let mut completed = 0;
+            for (_, buffer) in batch.iter() {
+                if remaining < buffer.len() {
+                    break;
+                }
+                remaining -= buffer.len();
+                results.push(Ok(buffer.len()));
+                completed += 1;
+            }
+
+            next += completed;
+            if next < end {
+                let (address, buffer) = &mut requests[next];
+                results.push(self.read_memory(*address, buffer));
+                next += 1;
+            }
+        }
+
+        results
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Result<usize> {
+        let local = libc::iovec {
+            iov_base: buffer.as_ptr().cast_mut().cast(),
+            iov_len: buffer.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+
+        let copied = unsafe { libc::process_vm_writev(self.pid, &local, 1, &remote, 1, 0) };
+        if copied >= 0 {
+            return Ok(copied as usize);
+        }
+
+        // only when the syscall itself is unavailable: /proc/<pid>/mem ignores page
+        // protections, and a write to a read-only page must fail here as it does on windows
+        match last_errno() {
+            libc::ENOSYS => self.mem_write(address, buffer, libc::ENOSYS),
+            errno => Err(mm_error::WriteFailed { address, status: errno }),
+        }
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        let maps = self.maps().map_err(|e| mm_error::ReadFailed {
+            address,
+            status: e.raw_os_error().unwrap_or(libc::EIO),
+        })?;
+        Ok(procfs::region_at(&maps, address))
+    }
+
+    /// One pass over `/proc/<pid>/maps` rather than a re-read per region.
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        let maps = self.maps().map_err(|e| mm_error::ReadFailed {
+            address: 0,
+            status: e.raw_os_error().unwrap_or(libc::EIO),
+        })?;
+        Ok(procfs::regions(&maps))
+    }
+
+    fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        p_handle::enumerate_modules(self)
+    }
+}
@@ -1,12 +1,58 @@
+// `#[derive(RemoteStruct)]` expands to `::mm::` paths, which must resolve inside this crate too.
+extern crate self as mm;
+
+#[cfg(windows)]
 pub mod ntapi;
+pub mod backend;
+pub mod batch;
+pub mod cpp;
+pub mod cursor;
+mod elf;
 pub mod module;
 pub mod handle;
 pub mod error;
 pub mod memory;
+#[cfg(windows)]
 pub mod mouse;
+#[cfg(target_os = "linux")]
+mod procfs;
+pub mod partial;
+pub mod pattern;
+pub mod pod;
+pub mod pointer_path;
+pub mod pointer_scan;
+pub mod region;
+pub mod remote;
+pub mod remote_ptr;
+pub mod scanner;
+pub mod snapshot;
+pub mod string;
 
+pub use backend::cache::CachedBackend;
+pub use backend::core_dump::CoreDump;
+pub use backend::local::CurrentProcess;
+pub use backend::minidump::Minidump;
+pub use backend::mock::MockProcess;
+pub use backend::MemoryBackend;
+pub use batch::ReadBatch;
+pub use cpp::{ContainerOptions, StdLib, StdReader};
+pub use cursor::RemoteCursor;
 pub use error::{mm_error, Result};
 pub use handle::{p_handle};
-pub use memory::{mmg};
+pub use memory::{mmg, PointerHop, PointerTrace, TypeReader};
 pub use module::ModuleInfo;
+pub use partial::{BestEffortRead, PartialRead};
+pub use pattern::{Pattern, RipOperand, ScanRange};
+pub use pod::{CheckedPod, Pod};
+pub use pointer_path::PointerPath;
+pub use pointer_scan::{PointerMap, PointerScanOptions};
+#[cfg(windows)]
 pub use mouse::Mouse;
+pub use region::{MemoryRegion, Protection, RegionKind, RegionState};
+pub use remote::RemoteStruct;
+pub use remote_ptr::{PointerWidth, RemoteChunks, RemotePtr, RemotePtr32, RemotePtr64, RemoteSlice};
+#[cfg(feature = "derive")]
+pub use mm_derive::RemoteStruct;
+pub use scanner::{ScanOptions, ScanPredicate, Value, ValueScanner, ValueType};
+pub use snapshot::{ChangedRange, DiffFilter, Snapshot};
+pub use string::{DecodeMode, StringEncoding};
@@ -1,38 +1,40 @@
 use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 
+use crate::backend::MemoryBackend;
 use crate::error::{mm_error, Result};
 use crate::handle::p_handle;
-use crate::ntapi::{nt_read_virtual_memory, nt_write_virtual_memory};
+use crate::module::{find_module_base, ModuleInfo};
+use crate::pod::{bytes_of, CheckedPod, Pod};
+use crate::region::{MemoryRegion, Protection, RegionState};
+use crate::string::{DecodeMode, StringEncoding};
 
+#[allow(non_camel_case_types)]
 #[derive(Debug)]
-pub struct mmg<'a> {
-    p_handle: &'a p_handle,
+pub struct mmg<'a, B: ?Sized = p_handle> {
+    backend: &'a B,
 }
 
-impl<'a> mmg<'a> {
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
     #[must_use]
     #[inline]
-    pub const fn new(handle: &'a p_handle) -> Self {
-        Self { p_handle: handle }
+    pub const fn new(backend: &'a B) -> Self {
+        Self { backend }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn backend(&self) -> &'a B {
+        self.backend
     }
 
     #[inline]
-    pub fn read<T>(&self, address: usize) -> Result<T>
-    where
-        T: Copy + Default,
-    {
-        let mut value = T::default();
+    pub fn read<T: Pod>(&self, address: usize) -> Result<T> {
+        let mut value = MaybeUninit::<T>::zeroed();
         let size = std::mem::size_of::<T>();
 
-        let bytes_read = unsafe {
-            nt_read_virtual_memory(
-                self.p_handle.as_raw(),
-                address,
-                std::ptr::addr_of_mut!(value).cast::<u8>(),
-                size,
-            )
-                .map_err(|status| mm_error::ReadFailed { address, status })?
-        };
+        let buffer = unsafe { std::slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), size) };
+        let bytes_read = self.backend.read_memory(address, buffer)?;
 
         if bytes_read != size {
             return Err(mm_error::InvalidBufferSize {
@@ -41,25 +43,26 @@ impl<'a> mmg<'a> {
             });
         }
 
-        Ok(value)
+        Ok(unsafe { value.assume_init() })
     }
 
     #[inline]
-    pub fn write<T>(&self, address: usize, value: &T) -> Result<usize>
-    where
-        T: Copy,
-    {
-        let size = std::mem::size_of::<T>();
+    pub fn write<T: Pod>(&self, address: usize, value: &T) -> Result<usize> {
+        self.backend.write_memory(address, bytes_of(value))
+    }
 
-        unsafe {
-            nt_write_virtual_memory(
-                self.p_handle.as_raw(),
-                address,
-                std::ptr::addr_of!(*value).cast::<u8>(),
-                size,
-            )
-                .map_err(|status| mm_error::WriteFailed { address, status })
-        }
+    /// Reads `T::Raw` and checks it, failing with `InvalidValue` on a bit pattern
+    /// that is not a valid `T`.
+    pub fn read_checked<T: CheckedPod>(&self, address: usize) -> Result<T> {
+        T::from_raw(self.read::<T::Raw>(address)?).ok_or(mm_error::InvalidValue {
+            address,
+            type_name: std::any::type_name::<T>(),
+        })
+    }
+
+    #[inline]
+    pub fn write_checked<T: CheckedPod>(&self, address: usize, value: T) -> Result<usize> {
+        self.write(address, &value.to_raw())
     }
 
     #[inline]
@@ -68,15 +71,7 @@ impl<'a> mmg<'a> {
             return Ok(0);
         }
 
-        unsafe {
-            nt_read_virtual_memory(
-                self.p_handle.as_raw(),
-                address,
-                buffer.as_mut_ptr(),
-                buffer.len(),
-            )
-                .map_err(|status| mm_error::ReadFailed { address, status })
-        }
+        self.backend.read_memory(address, buffer)
     }
 
     #[inline]
@@ -85,10 +80,40 @@ impl<'a> mmg<'a> {
             return Ok(0);
         }
 
-        unsafe {
-            nt_write_virtual_memory(self.p_handle.as_raw(), address, buffer.as_ptr(), buffer.len())
-                .map_err(|status| mm_error::WriteFailed { address, status })
+        self.backend.write_memory(address, buffer)
+    }
+
+    #[inline]
+    pub fn query_region(&self, address: usize) -> Result<MemoryRegion> {
+        self.backend.query_region(address)
+    }
+
+    /// All regions of the address space in ascending order, free gaps included.
+    pub fn query_regions(&self) -> Result<std::vec::IntoIter<MemoryRegion>> {
+        Ok(self.backend.regions()?.into_iter())
+    }
+
+    /// Like `query_region`, but an address that falls in free space is an error.
+    pub fn region_at(&self, address: usize) -> Result<MemoryRegion> {
+        let region = self.backend.query_region(address)?;
+        if region.state == RegionState::Free {
+            return Err(mm_error::MemoryNotAccessable(address));
         }
+        Ok(region)
+    }
+
+    #[inline]
+    pub fn protect_region(&self, address: usize, size: usize, protection: Protection) -> Result<Protection> {
+        self.backend.protect_region(address, size, protection)
+    }
+
+    #[inline]
+    pub fn enumerate_modules(&self) -> Result<Vec<ModuleInfo>> {
+        self.backend.enumerate_modules()
+    }
+
+    pub fn get_module_base(&self, module_name: &str) -> Result<usize> {
+        find_module_base(&self.backend.enumerate_modules()?, module_name)
     }
 
     pub fn read_bytes_vec(&self, address: usize, size: usize) -> Result<Vec<u8>> {
@@ -98,14 +123,10 @@ impl<'a> mmg<'a> {
         Ok(buffer)
     }
 
+    /// Null-terminated UTF-8 string of at most `max_length` bytes, read page by page.
+    /// Invalid UTF-8 fails with `StringDecodeFailed`; see `read_string_lossy`.
     pub fn read_string(&self, address: usize, max_length: usize) -> Result<String> {
-        let buffer = self.read_bytes_vec(address, max_length)?;
-        let null_pos = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
-
-        String::from_utf8(buffer[..null_pos].to_vec()).map_err(|_| mm_error::InvalidBufferSize {
-            expected: max_length,
-            actual: null_pos,
-        })
+        self.read_string_as(address, max_length, StringEncoding::Utf8, DecodeMode::Strict)
     }
 
     pub fn write_string(&self, address: usize, string: &str) -> Result<usize> {
@@ -114,21 +135,9 @@ impl<'a> mmg<'a> {
         self.write_bytes(address, &bytes)
     }
 
+    /// Null-terminated UTF-16 string of at most `max_chars` code units, read page by page.
     pub fn read_wstring(&self, address: usize, max_chars: usize) -> Result<String> {
-        let byte_count = max_chars * 2;
-        let buffer = self.read_bytes_vec(address, byte_count)?;
-
-        let u16_buffer: Vec<u16> = buffer
-            .chunks_exact(2)
-            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
-            .collect();
-
-        let null_pos = u16_buffer.iter().position(|&c| c == 0).unwrap_or(u16_buffer.len());
-
-        String::from_utf16(&u16_buffer[..null_pos]).map_err(|_| mm_error::InvalidBufferSize {
-            expected: max_chars,
-            actual: null_pos,
-        })
+        self.read_string_as(address, max_chars, StringEncoding::Utf16Le, DecodeMode::Strict)
     }
 
     pub fn write_wstring(&self, address: usize, string: &str) -> Result<usize> {
@@ -154,35 +163,106 @@ impl<'a> mmg<'a> {
         Ok(address)
     }
 
+    /// `read_pointer_chain`, keeping every dereference along the way. A hop that
+    /// fails, or reads a null pointer, ends the walk with `PointerChainBroken`
+    /// naming that hop and carrying the hops read before it.
+    pub fn trace_pointer_chain(&self, base: usize, offsets: &[usize]) -> Result<PointerTrace> {
+        let mut hops = Vec::with_capacity(offsets.len().saturating_sub(1));
+        let mut address = base;
+
+        for (index, &offset) in offsets.iter().enumerate() {
+            address = address.wrapping_add(offset);
+
+            if index < offsets.len() - 1 {
+                let value = match self.read::<usize>(address) {
+                    Ok(0) => Err(mm_error::NullPointer),
+                    other => other,
+                };
+
+                match value {
+                    Ok(value) => {
+                        hops.push(PointerHop { address, value });
+                        address = value;
+                    }
+                    Err(source) => {
+                        return Err(mm_error::PointerChainBroken {
+                            hop: index,
+                            address,
+                            source: Box::new(source),
+                            hops,
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(PointerTrace { hops, address })
+    }
+
+    /// Absolute target of a `[rip+disp32]` (or `call`/`jmp rel32`) operand: the
+    /// signed displacement at `address + displacement_offset`, relative to the end
+    /// of the `instruction_len`-byte instruction starting at `address`.
+    pub fn resolve_rip(&self, address: usize, displacement_offset: usize, instruction_len: usize) -> Result<usize> {
+        let displacement = self.read::<i32>(address.wrapping_add(displacement_offset))?;
+        Ok(rip_target(address, displacement, instruction_len))
+    }
+
     #[must_use]
     #[inline]
-    pub const fn typed<T: Copy + Default>(&self) -> TypeReader<'a, T> {
+    pub const fn typed<T: Pod>(&self) -> TypeReader<'a, T, B> {
         TypeReader {
-            handle: self.p_handle,
+            backend: self.backend,
             _phantom: PhantomData,
         }
     }
 }
 
+#[must_use]
+#[inline]
+const fn rip_target(address: usize, displacement: i32, instruction_len: usize) -> usize {
+    address.wrapping_add(instruction_len).wrapping_add_signed(displacement as isize)
+}
+
+/// One dereference of a pointer chain: the pointer-sized `value` read at `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerHop {
+    pub address: usize,
+    pub value: usize,
+}
+
+impl std::fmt::Display for PointerHop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[0x{:016X}] -> 0x{:016X}", self.address, self.value)
+    }
+}
+
+/// Result of `trace_pointer_chain`: the hops in order and the final address, which is
+/// what `read_pointer_chain` returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerTrace {
+    pub hops: Vec<PointerHop>,
+    pub address: usize,
+}
+
 #[derive(Debug)]
-pub struct TypeReader<'a, T> {
-    handle: &'a p_handle,
+pub struct TypeReader<'a, T, B: ?Sized = p_handle> {
+    backend: &'a B,
     _phantom: PhantomData<T>,
 }
 
-impl<'a, T: Copy + Default> TypeReader<'a, T> {
+impl<'a, T: Pod, B: MemoryBackend + ?Sized> TypeReader<'a, T, B> {
     #[inline]
     pub fn read(&self, address: usize) -> Result<T> {
-        mmg::new(self.handle).read(address)
+        mmg::new(self.backend).read(address)
     }
 
     #[inline]
     pub fn write(&self, address: usize, value: &T) -> Result<usize> {
-        mmg::new(self.handle).write(address, value)
+        mmg::new(self.backend).write(address, value)
     }
 
     pub fn read_array(&self, address: usize, count: usize) -> Result<Vec<T>> {
-        let reader = mmg::new(self.handle);
+        let reader = mmg::new(self.backend);
         let size = std::mem::size_of::<T>();
         let mut result = Vec::with_capacity(count);
 
@@ -195,7 +275,7 @@ impl<'a, T: Copy + Default> TypeReader<'a, T> {
     }
 
     pub fn write_array(&self, address: usize, values: &[T]) -> Result<usize> {
-        let reader = mmg::new(self.handle);
+        let reader = mmg::new(self.backend);
         let size = std::mem::size_of::<T>();
         let mut total_written = 0;
 
@@ -205,4 +285,132 @@ impl<'a, T: Copy + Default> TypeReader<'a, T> {
 
         Ok(total_written)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::local::CurrentProcess;
+
+    #[repr(C)]
+    struct Node {
+        pad: u64,
+        next: *const Node,
+        value: u32,
+    }
+
+    #[test]
+    fn pointer_chain_follows_heap_nodes() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let tail = Box::new(Node { pad: 0, next: std::ptr::null(), value: 42 });
+        let head = Box::new(Node { pad: 0, next: &raw const *tail, value: 1 });
+        let root: *const Node = &raw const *head;
+
+        let next_offset = std::mem::offset_of!(Node, next);
+        let value_offset = std::mem::offset_of!(Node, value);
+
+        let address = mem
+            .read_pointer_chain(&raw const root as usize, &[0, next_offset, value_offset])
+            .unwrap();
+
+        assert_eq!(address, &raw const tail.value as usize);
+        assert_eq!(mem.read::<u32>(address).unwrap(), 42);
+        assert_eq!(mem.read_pointer_chain(0x1000, &[]).unwrap(), 0x1000);
+    }
+
+    #[test]
+    fn pointer_chain_reports_unreadable_hop() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let dangling: usize = 0x10;
+        let result = mem.read_pointer_chain(&raw const dangling as usize, &[0, 0, 0]);
+        assert!(matches!(result, Err(mm_error::MemoryNotAccessable(0x10))));
+    }
+
+    #[test]
+    fn pointer_chain_trace_names_the_failing_hop() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let tail = Box::new(Node { pad: 0, next: std::ptr::null(), value: 42 });
+        let head = Box::new(Node { pad: 0, next: &raw const *tail, value: 1 });
+        let root: *const Node = &raw const *head;
+        let next_offset = std::mem::offset_of!(Node, next);
+        let value_offset = std::mem::offset_of!(Node, value);
+
+        let offsets = [0, next_offset, value_offset];
+        let trace = mem.trace_pointer_chain(&raw const root as usize, &offsets).unwrap();
+        assert_eq!(
+            trace.hops,
+            [
+                PointerHop { address: &raw const root as usize, value: root as usize },
+                PointerHop { address: &raw const head.next as usize, value: &raw const *tail as usize },
+            ]
+        );
+        assert_eq!(trace.address, mem.read_pointer_chain(&raw const root as usize, &offsets).unwrap());
+
+        let broken = mem.trace_pointer_chain(&raw const root as usize, &[0, next_offset, next_offset, value_offset]);
+        match broken {
+            Err(mm_error::PointerChainBroken { hop, address, source, hops }) => {
+                assert_eq!((hop, address), (2, &raw const tail.next as usize));
+                assert!(matches!(*source, mm_error::NullPointer));
+                assert_eq!(hops.len(), 2);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let dangling: usize = 0x10;
+        let unreadable = mem.trace_pointer_chain(&raw const dangling as usize, &[0, 0, 0]).unwrap_err();
+        assert!(unreadable.to_string().contains("hop 1"), "{}", unreadable);
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let mut buffer = vec![0xFFu8; 64];
+        let address = buffer.as_mut_ptr() as usize;
+
+        assert_eq!(mem.write_string(address, "hello").unwrap(), 6);
+        assert_eq!(mem.read_string(address, 64).unwrap(), "hello");
+
+        let wide: Vec<u16> = "wide str\0junk".encode_utf16().collect();
+        assert_eq!(mem.read_wstring(wide.as_ptr() as usize, wide.len()).unwrap(), "wide str");
+        assert_eq!(mem.read_wstring(wide.as_ptr() as usize, 4).unwrap(), "wide");
+
+        mem.write_wstring(address, "ütf16").unwrap();
+        assert_eq!(mem.read_wstring(address, 32).unwrap(), "ütf16");
+        std::hint::black_box(&buffer);
+    }
+
+    #[test]
+    fn typed_arrays_round_trip() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+        let reader = mem.typed::<u32>();
+
+        let source = [1u32, 2, 3, 4, 5];
+        assert_eq!(reader.read_array(source.as_ptr() as usize, 5).unwrap(), source);
+        assert!(reader.read_array(source.as_ptr() as usize, 0).unwrap().is_empty());
+
+        let mut target = vec![0u32; 5];
+        let written = reader.write_array(target.as_mut_ptr() as usize, &[9, 8, 7]).unwrap();
+        assert_eq!(written, 12);
+        assert_eq!(std::hint::black_box(&target)[..], [9, 8, 7, 0, 0]);
+        assert_eq!(reader.read(target.as_ptr() as usize).unwrap(), 9);
+    }
+
+    #[test]
+    fn read_bytes_vec_stops_at_unmapped_memory() {
+        let backend = unsafe { CurrentProcess::new() };
+        let mem = mmg::new(&backend);
+
+        let bytes = [7u8; 16];
+        assert_eq!(mem.read_bytes_vec(bytes.as_ptr() as usize, 16).unwrap(), bytes);
+        assert!(mem.read_bytes_vec(0, 16).is_err());
+        assert_eq!(mem.read_bytes(0, &mut []).unwrap(), 0);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::{mm_error, Result};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModuleInfo {
     pub name: String,
@@ -58,4 +60,14 @@ impl std::fmt::Display for ModuleInfo {
             self.name, self.addy, self.size, self.entry_point
         )
     }
-}
\ No newline at end of file
+}
+
+pub(crate) fn find_module_base(modules: &[ModuleInfo], module_name: &str) -> Result<usize> {
+    let target_name = module_name.to_lowercase();
+
+    modules
+        .iter()
+        .find(|m| m.name.to_lowercase() == target_name)
+        .map(|m| m.addy)
+        .ok_or_else(|| mm_error::ModuleNotFound(module_name.to_string()))
+}
@@ -7,6 +7,8 @@ pub struct Point {
     pub y: i32,
 }
 
+// spelled as in the Win32 headers the constants below come from
+#[allow(clippy::upper_case_acronyms)]
 type DWORD = u32;
 
 const MOUSEEVENTF_LEFTDOWN: DWORD = 0x0002;
@@ -1,7 +1,12 @@
-use windows_sys::Win32::Foundation::{HANDLE, NTSTATUS};
+use windows_sys::Win32::Foundation::{GetLastError, HANDLE, NTSTATUS};
+use windows_sys::Win32::System::Memory::*;
+
+use crate::error::{mm_error, Result};
+use crate::region::{MemoryRegion, Protection, RegionKind, RegionState};
 
 pub const STATUS_SUCCESS: NTSTATUS = 0x00;
 pub const STATUS_PARTIAL_COPY: NTSTATUS = 0x8000_000D_u32 as i32;
+pub const STATUS_ACCESS_VIOLATION: NTSTATUS = 0xC000_0005_u32 as i32;
 
 pub const PROCESS_VM_READ: u32 = 0x0010;
 pub const PROCESS_VM_WRITE: u32 = 0x0020;
@@ -30,22 +35,26 @@ unsafe extern "system" {
     ) -> NTSTATUS;
 }
 
+/// # Safety
+/// `buffer` must be valid for writes of `size` bytes.
 #[inline]
 pub unsafe fn nt_read_virtual_memory(
     process_handle: HANDLE,
     address: usize,
     buffer: *mut u8,
     size: usize,
-) -> Result<usize, NTSTATUS> {
+) -> std::result::Result<usize, NTSTATUS> {
     let mut bytes_read: usize = 0;
 
-    let status = NtReadVirtualMemory(
-        process_handle,
-        address as *const core::ffi::c_void,
-        buffer.cast::<core::ffi::c_void>(),
-        size,
-        &mut bytes_read,
-    );
+    let status = unsafe {
+        NtReadVirtualMemory(
+            process_handle,
+            address as *const core::ffi::c_void,
+            buffer.cast::<core::ffi::c_void>(),
+            size,
+            &mut bytes_read,
+        )
+    };
 
     if status == STATUS_SUCCESS {
         Ok(bytes_read)
@@ -54,22 +63,26 @@ pub unsafe fn nt_read_virtual_memory(
     }
 }
 
+/// # Safety
+/// `buffer` must be valid for reads of `size` bytes.
 #[inline]
 pub unsafe fn nt_write_virtual_memory(
     process_handle: HANDLE,
     address: usize,
     buffer: *const u8,
     size: usize,
-) -> Result<usize, NTSTATUS> {
+) -> std::result::Result<usize, NTSTATUS> {
     let mut bytes_written: usize = 0;
 
-    let status = NtWriteVirtualMemory(
-        process_handle,
-        address as *mut core::ffi::c_void,
-        buffer.cast::<core::ffi::c_void>(),
-        size,
-        &mut bytes_written,
-    );
+    let status = unsafe {
+        NtWriteVirtualMemory(
+            process_handle,
+            address as *mut core::ffi::c_void,
+            buffer.cast::<core::ffi::c_void>(),
+            size,
+            &mut bytes_written,
+        )
+    };
 
     if status == STATUS_SUCCESS {
         Ok(bytes_written)
@@ -100,4 +113,87 @@ pub const fn nt_warning(status: NTSTATUS) -> bool {
 #[must_use]
 pub const fn nt_error(status: NTSTATUS) -> bool {
     (status as u32 >> 30) == 3
-}
\ No newline at end of file
+}
+
+pub(crate) fn virtual_query(process_handle: HANDLE, address: usize) -> Result<MemoryRegion> {
+    let mut info: MEMORY_BASIC_INFORMATION = unsafe { std::mem::zeroed() };
+
+    let written = unsafe {
+        VirtualQueryEx(
+            process_handle,
+            address as *const core::ffi::c_void,
+            &mut info,
+            std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+        )
+    };
+
+    if written == 0 {
+        return Err(mm_error::WindowsApiError(unsafe { GetLastError() }));
+    }
+
+    let protection = if info.State == MEM_COMMIT {
+        Protection::from_page_flags(info.Protect)
+    } else {
+        Protection::NONE
+    };
+
+    let mut region = MemoryRegion::new(info.BaseAddress as usize, info.RegionSize, protection);
+    region.state = match info.State {
+        MEM_COMMIT => RegionState::Commit,
+        MEM_RESERVE => RegionState::Reserve,
+        _ => RegionState::Free,
+    };
+    region.kind = match info.Type {
+        MEM_IMAGE => RegionKind::Image,
+        MEM_MAPPED => RegionKind::Mapped,
+        MEM_PRIVATE => RegionKind::Private,
+        _ => RegionKind::Unknown,
+    };
+    if matches!(region.kind, RegionKind::Image | RegionKind::Mapped) {
+        region.mapped_file = mapped_file_name(process_handle, region.base);
+    }
+
+    Ok(region)
+}
+
+/// Native (`\Device\HarddiskVolumeN\...`) path of the file mapped at `address`.
+fn mapped_file_name(process_handle: HANDLE, address: usize) -> Option<String> {
+    use windows_sys::Win32::System::ProcessStatus::GetMappedFileNameW;
+
+    let mut buffer = [0u16; 1024];
+    let len = unsafe {
+        GetMappedFileNameW(
+            process_handle,
+            address as *const core::ffi::c_void,
+            buffer.as_mut_ptr(),
+            buffer.len() as u32,
+        )
+    };
+
+    (len != 0).then(|| String::from_utf16_lossy(&buffer[..len as usize]))
+}
+
+pub(crate) fn virtual_protect(
+    process_handle: HANDLE,
+    address: usize,
+    size: usize,
+    protection: Protection,
+) -> Result<Protection> {
+    let mut old: PAGE_PROTECTION_FLAGS = 0;
+
+    let success = unsafe {
+        VirtualProtectEx(
+            process_handle,
+            address as *const core::ffi::c_void,
+            size,
+            protection.to_page_flags(),
+            &mut old,
+        )
+    };
+
+    if success == 0 {
+        return Err(mm_error::WindowsApiError(unsafe { GetLastError() }));
+    }
+
+    Ok(Protection::from_page_flags(old))
+}
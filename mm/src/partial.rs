@@ -0,0 +1,262 @@
+use std::ops::Range;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::pattern::PAGE_SIZE;
+
+/// Outcome of `read_bytes_partial`: how much of the buffer was filled, and where reading
+/// stopped when it was not all of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialRead {
+    pub read: usize,
+    /// Address of the first byte that could not be read, `None` when the read completed.
+    pub fault: Option<usize>,
+}
+
+impl PartialRead {
+    #[must_use]
+    #[inline]
+    pub const fn is_complete(&self) -> bool {
+        self.fault.is_none()
+    }
+}
+
+/// Outcome of `read_best_effort`: every requested byte, with unreadable pages filled, and
+/// which pages were really read. Pages are counted from `base`, the first one ending at
+/// the next page boundary. A page is valid only when all of its bytes were read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestEffortRead {
+    pub base: usize,
+    pub data: Vec<u8>,
+    valid: Vec<u64>,
+}
+
+impl BestEffortRead {
+    /// Pages touched by `data`, 0 when it is empty.
+    #[must_use]
+    pub fn page_count(&self) -> usize {
+        match self.data.len() {
+            0 => 0,
+            len => page_index(self.base, self.base + len - 1) + 1,
+        }
+    }
+
+    #[must_use]
+    pub fn is_page_valid(&self, index: usize) -> bool {
+        self.valid.get(index / 64).is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Whether the byte at `address` came from the target.
+    #[must_use]
+    pub fn is_valid(&self, address: usize) -> bool {
+        address >= self.base && address - self.base < self.data.len() && self.is_page_valid(page_index(self.base, address))
+    }
+
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        (0..self.page_count()).all(|index| self.is_page_valid(index))
+    }
+
+    /// Runs of valid bytes, as address ranges.
+    pub fn valid_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let end = self.base + self.data.len();
+        let mut index = 0;
+
+        std::iter::from_fn(move || {
+            while index < self.page_count() && !self.is_page_valid(index) {
+                index += 1;
+            }
+            let start = page_start(self.base, index).min(end);
+            while index < self.page_count() && self.is_page_valid(index) {
+                index += 1;
+            }
+            let stop = page_start(self.base, index).min(end);
+            (start < stop).then_some(start..stop)
+        })
+    }
+
+    fn mark_valid(&mut self, index: usize) {
+        self.valid[index / 64] |= 1 << (index % 64);
+    }
+}
+
+#[inline]
+const fn page_index(base: usize, address: usize) -> usize {
+    address / PAGE_SIZE - base / PAGE_SIZE
+}
+
+/// Start of page `index`, the first page starting at `base` itself.
+#[inline]
+const fn page_start(base: usize, index: usize) -> usize {
+    if index == 0 { base } else { (base / PAGE_SIZE + index) * PAGE_SIZE }
+}
+
+/// Read statuses that mean the memory itself is not readable.
+#[cfg(windows)]
+const FAULT_STATUSES: &[i32] = &[crate::ntapi::STATUS_PARTIAL_COPY, crate::ntapi::STATUS_ACCESS_VIOLATION];
+#[cfg(target_os = "linux")]
+const FAULT_STATUSES: &[i32] = &[libc::EFAULT, libc::EIO];
+#[cfg(not(any(windows, target_os = "linux")))]
+const FAULT_STATUSES: &[i32] = &[];
+
+/// Errors a backend reports for memory that is simply not readable, as opposed to a
+/// dead handle, missing rights or any other failed read.
+fn is_fault(error: &mm_error) -> bool {
+    match error {
+        mm_error::MemoryNotAccessable(_) => true,
+        mm_error::ReadFailed { status, .. } => FAULT_STATUSES.contains(status),
+        _ => false,
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// Fills as much of `buffer` as is readable from `address`, stopping at the first fault.
+    /// Unlike `read_bytes`, an unreadable first byte is not an error but a read of 0 bytes
+    /// with the fault at `address`. Only non-fault errors (handle, rights) are returned.
+    pub fn read_bytes_partial(&self, address: usize, buffer: &mut [u8]) -> Result<PartialRead> {
+        let mut done = 0;
+
+        while done < buffer.len() {
+            let cursor = address.wrapping_add(done);
+
+            match self.read_bytes(cursor, &mut buffer[done..]) {
+                Ok(read) if read > 0 => {
+                    done += read;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(error) if is_fault(&error) => {}
+                Err(error) => return Err(error),
+            }
+
+            // Some backends give up on the whole span; retry up to the page end alone.
+            let page_left = PAGE_SIZE - cursor % PAGE_SIZE;
+            if page_left < buffer.len() - done {
+                match self.read_bytes(cursor, &mut buffer[done..done + page_left]) {
+                    Ok(read) if read > 0 => {
+                        done += read;
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(error) if is_fault(&error) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+
+            return Ok(PartialRead {
+                read: done,
+                fault: Some(cursor),
+            });
+        }
+
+        Ok(PartialRead { read: done, fault: None })
+    }
+
+    /// Reads `len` bytes from `address` across unreadable pages (guard pages, holes), filling
+    /// what cannot be read with `fill`. Only non-fault errors are returned.
+    pub fn read_best_effort(&self, address: usize, len: usize, fill: u8) -> Result<BestEffortRead> {
+        let mut result = BestEffortRead {
+            base: address,
+            data: vec![fill; len],
+            valid: Vec::new(),
+        };
+        if len == 0 {
+            return Ok(result);
+        }
+        result.valid = vec![0; result.page_count().div_ceil(64)];
+
+        let mut done = 0;
+        while done < len {
+            let partial = self.read_bytes_partial(address + done, &mut result.data[done..])?;
+            let first = page_index(address, address + done);
+
+            let Some(fault) = partial.fault else {
+                (first..result.page_count()).for_each(|index| result.mark_valid(index));
+                break;
+            };
+
+            // Pages before the faulting one were read in full; skip the rest of that one.
+            let fault_page = page_index(address, fault);
+            (first..fault_page).for_each(|index| result.mark_valid(index));
+
+            let next_page = (page_start(address, fault_page + 1) - address).min(len);
+            result.data[done + partial.read..next_page].fill(fill);
+            done = next_page;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::{MockFault, MockProcess};
+    use crate::region::Protection;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map(0x1000, vec![0x11; 0x1000], Protection::READ_WRITE);
+        mock.map(0x2000, vec![0x22; 0x1000], Protection::NONE);
+        mock.map(0x3000, vec![0x33; 0x1800], Protection::READ_WRITE);
+        mock
+    }
+
+    #[test]
+    fn partial_reads_keep_the_readable_prefix() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let mut buffer = [0u8; 0x20];
+        let partial = mem.read_bytes_partial(0x1FF0, &mut buffer).unwrap();
+        assert_eq!(partial, PartialRead { read: 0x10, fault: Some(0x2000) });
+        assert_eq!(buffer[..0x10], [0x11; 0x10]);
+
+        let partial = mem.read_bytes_partial(0x2010, &mut buffer).unwrap();
+        assert_eq!(partial, PartialRead { read: 0, fault: Some(0x2010) });
+
+        let partial = mem.read_bytes_partial(0x3000, &mut buffer).unwrap();
+        assert!(partial.is_complete());
+        assert_eq!(partial.read, 0x20);
+
+        mock.inject_fault_once(0x1000..0x1001, MockFault::AccessDenied);
+        assert!(matches!(mem.read_bytes_partial(0x1000, &mut buffer), Err(mm_error::AccessDenied)));
+
+        mock.inject_fault(0x3000..0x3010, MockFault::Status(FAULT_STATUSES[0]));
+        assert_eq!(mem.read_bytes_partial(0x3000, &mut buffer).unwrap().fault, Some(0x3000));
+        mock.clear_faults();
+        mock.inject_fault(0x3000..0x3010, MockFault::Status(-1));
+        assert!(matches!(mem.read_best_effort(0x3000, 0x20, 0), Err(mm_error::ReadFailed { status: -1, .. })));
+    }
+
+    #[test]
+    fn best_effort_fills_unreadable_pages() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let read = mem.read_best_effort(0x1800, 0x3400, 0xCC).unwrap();
+        assert_eq!(read.page_count(), 4);
+        let pages: Vec<_> = (0..4).map(|i| read.is_page_valid(i)).collect();
+        // 0x1800.., 0x2000 (no access), 0x3000, 0x4000 (half mapped)
+        assert_eq!(pages, [true, false, true, false]);
+
+        assert_eq!(read.data[..0x800], [0x11; 0x800]);
+        assert_eq!(read.data[0x800..0x1800], [0xCC; 0x1000]);
+        assert_eq!(read.data[0x1800..0x2800], [0x33; 0x1000]);
+        assert_eq!(read.data[0x2800..0x3000], [0x33; 0x800]);
+        assert_eq!(read.data[0x3000..], [0xCC; 0x400]);
+
+        assert!(read.is_valid(0x1800) && !read.is_valid(0x2000) && read.is_valid(0x3FFF));
+        assert!(!read.is_valid(0x4000) && !read.is_valid(0x1000));
+        assert_eq!(read.valid_ranges().collect::<Vec<_>>(), [0x1800..0x2000, 0x3000..0x4000]);
+        assert!(!read.is_complete());
+
+        let clean = mem.read_best_effort(0x3000, 0x1000, 0).unwrap();
+        assert!(clean.is_complete());
+
+        let empty = mem.read_best_effort(0x3000, 0, 0).unwrap();
+        assert!(empty.data.is_empty() && empty.is_complete());
+        assert_eq!((empty.page_count(), empty.valid_ranges().count()), (0, 0));
+    }
+}
@@ -0,0 +1,446 @@
+use memchr::memmem::Finder;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::region::MemoryRegion;
+
+pub(crate) const SCAN_CHUNK: usize = 0x10_0000;
+pub(crate) const PAGE_SIZE: usize = 0x1000;
+
+/// A byte signature where every position is either an exact byte or a wildcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    bytes: Vec<u8>,
+    mask: Vec<bool>,
+}
+
+impl Pattern {
+    /// Parses either IDA style (`"48 8B 05 ?? ?? ?? ??"`, single `?` also accepted)
+    /// or an escaped code-style string (`"\x48\x8B\x05"`) with every byte exact.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let invalid = |what: &str| mm_error::InvalidPattern(format!("{} in \"{}\"", what, pattern));
+
+        let (bytes, mask): (Vec<u8>, Vec<bool>) = if pattern.contains("\\x") {
+            pattern
+                .split("\\x")
+                .skip_while(|s| s.trim().is_empty())
+                .map(|token| match token.trim() {
+                    "??" | "?" => Ok((0, false)),
+                    hex => u8::from_str_radix(hex, 16).map(|b| (b, true)).map_err(|_| invalid(token)),
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip()
+        } else {
+            pattern
+                .split_whitespace()
+                .map(|token| match token {
+                    "??" | "?" => Ok((0, false)),
+                    hex if hex.len() == 2 => u8::from_str_radix(hex, 16).map(|b| (b, true)).map_err(|_| invalid(token)),
+                    _ => Err(invalid(token)),
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip()
+        };
+
+        Self::from_parts(bytes, mask)
+    }
+
+    /// Code-style signature: raw bytes plus a mask of `x` (exact) and `?` (wildcard).
+    pub fn from_code(bytes: impl AsRef<[u8]>, mask: &str) -> Result<Self> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != mask.len() {
+            return Err(mm_error::InvalidPattern(format!(
+                "mask length {} does not match {} bytes",
+                mask.len(),
+                bytes.len()
+            )));
+        }
+
+        let mask = mask
+            .chars()
+            .map(|c| match c {
+                'x' | 'X' => Ok(true),
+                '?' => Ok(false),
+                other => Err(mm_error::InvalidPattern(format!("mask character '{}'", other))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::from_parts(bytes.to_vec(), mask)
+    }
+
+    fn from_parts(bytes: Vec<u8>, mask: Vec<bool>) -> Result<Self> {
+        if !mask.iter().any(|&exact| exact) {
+            return Err(mm_error::InvalidPattern("pattern has no exact bytes".to_string()));
+        }
+
+        Ok(Self { bytes, mask })
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Whether `data` starts with this pattern.
+    #[must_use]
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.len() >= self.len()
+            && self
+                .bytes
+                .iter()
+                .zip(&self.mask)
+                .zip(data)
+                .all(|((&expected, &exact), &actual)| !exact || expected == actual)
+    }
+
+    /// Longest run of exact bytes, searched with `memmem` before the full pattern is checked.
+    fn anchor(&self) -> (usize, &[u8]) {
+        let mut best = (0, 0);
+        let mut run_start = 0;
+
+        for (i, &exact) in self.mask.iter().enumerate() {
+            if !exact {
+                run_start = i + 1;
+            } else if i + 1 - run_start > best.1 {
+                best = (run_start, i + 1 - run_start);
+            }
+        }
+
+        (best.0, &self.bytes[best.0..best.0 + best.1])
+    }
+
+    /// Offsets of every match inside `data`, overlapping matches included.
+    #[must_use]
+    pub fn find_all(&self, data: &[u8]) -> Vec<usize> {
+        let (anchor_at, anchor) = self.anchor();
+        self.find_with(&Finder::new(anchor), anchor_at, data)
+    }
+
+    fn find_with(&self, finder: &Finder<'_>, anchor_at: usize, data: &[u8]) -> Vec<usize> {
+        finder
+            .find_iter(data)
+            .filter_map(|hit| hit.checked_sub(anchor_at))
+            .filter(|&start| self.matches(&data[start..]))
+            .collect()
+    }
+}
+
+impl std::str::FromStr for Pattern {
+    type Err = mm_error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (byte, &exact)) in self.bytes.iter().zip(&self.mask).enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if exact {
+                write!(f, "{:02X}", byte)?;
+            } else {
+                f.write_str("??")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the rip-relative operand sits relative to a pattern match, for
+/// `find_pattern_resolved`. `lea rax, [rip+disp32]` matched at its first byte
+/// is `RipOperand::new(3, 7)`; a `call rel32` is `RipOperand::new(1, 5)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RipOperand {
+    pub instruction_offset: usize,
+    pub displacement_offset: usize,
+    pub instruction_len: usize,
+}
+
+impl RipOperand {
+    #[must_use]
+    pub const fn new(displacement_offset: usize, instruction_len: usize) -> Self {
+        Self {
+            instruction_offset: 0,
+            displacement_offset,
+            instruction_len,
+        }
+    }
+
+    /// For patterns where the instruction starts `offset` bytes into the match.
+    #[must_use]
+    pub const fn at(self, offset: usize) -> Self {
+        Self {
+            instruction_offset: offset,
+            ..self
+        }
+    }
+}
+
+/// Address spans to scan. Built from a module, a plain range or a set of regions;
+/// unreadable regions are dropped and adjacent ones merged, so matches may straddle them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanRange {
+    spans: Vec<(usize, usize)>,
+}
+
+impl ScanRange {
+    #[must_use]
+    pub fn new(start: usize, end: usize) -> Self {
+        let mut range = Self::default();
+        range.push(start, end);
+        range
+    }
+
+    fn push(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => self.spans.push((start, end)),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn spans(&self) -> &[(usize, usize)] {
+        &self.spans
+    }
+}
+
+impl From<&ModuleInfo> for ScanRange {
+    fn from(module: &ModuleInfo) -> Self {
+        Self::new(module.addy, module.end_address())
+    }
+}
+
+impl From<std::ops::Range<usize>> for ScanRange {
+    fn from(range: std::ops::Range<usize>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<'r> FromIterator<&'r MemoryRegion> for ScanRange {
+    fn from_iter<I: IntoIterator<Item = &'r MemoryRegion>>(regions: I) -> Self {
+        let mut readable: Vec<_> = regions.into_iter().filter(|r| r.is_readable()).collect();
+        readable.sort_by_key(|r| r.base);
+
+        let mut range = Self::default();
+        for region in readable {
+            range.push(region.base, region.end_address());
+        }
+        range
+    }
+}
+
+impl FromIterator<MemoryRegion> for ScanRange {
+    fn from_iter<I: IntoIterator<Item = MemoryRegion>>(regions: I) -> Self {
+        regions.into_iter().collect::<Vec<_>>().iter().collect()
+    }
+}
+
+impl From<&[MemoryRegion]> for ScanRange {
+    fn from(regions: &[MemoryRegion]) -> Self {
+        regions.iter().collect()
+    }
+}
+
+impl From<Vec<MemoryRegion>> for ScanRange {
+    fn from(regions: Vec<MemoryRegion>) -> Self {
+        regions.iter().collect()
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// Every address in `range` matching `pattern`, in ascending order. See `Pattern::parse`
+    /// for the accepted syntaxes.
+    pub fn find_pattern(&self, pattern: &str, range: impl Into<ScanRange>) -> Result<Vec<usize>> {
+        self.scan_pattern(&Pattern::parse(pattern)?, range)
+    }
+
+    /// Like `find_pattern`, with a pattern that was already parsed or built with `Pattern::from_code`.
+    pub fn scan_pattern(&self, pattern: &Pattern, range: impl Into<ScanRange>) -> Result<Vec<usize>> {
+        let (anchor_at, anchor) = pattern.anchor();
+        let finder = Finder::new(anchor);
+        let mut matches = Vec::new();
+
+        for &(start, end) in range.into().spans() {
+            self.scan_span(pattern, &finder, anchor_at, start, end, SCAN_CHUNK, &mut matches);
+        }
+
+        Ok(matches)
+    }
+
+    /// Like `find_pattern`, but every match is turned into the absolute target of its
+    /// rip-relative operand. Matches whose displacement cannot be read are dropped.
+    pub fn find_pattern_resolved(
+        &self,
+        pattern: &str,
+        range: impl Into<ScanRange>,
+        operand: RipOperand,
+    ) -> Result<Vec<usize>> {
+        self.scan_pattern_resolved(&Pattern::parse(pattern)?, range, operand)
+    }
+
+    pub fn scan_pattern_resolved(
+        &self,
+        pattern: &Pattern,
+        range: impl Into<ScanRange>,
+        operand: RipOperand,
+    ) -> Result<Vec<usize>> {
+        Ok(self
+            .scan_pattern(pattern, range)?
+            .into_iter()
+            .filter_map(|at| {
+                self.resolve_rip(
+                    at.wrapping_add(operand.instruction_offset),
+                    operand.displacement_offset,
+                    operand.instruction_len,
+                )
+                .ok()
+            })
+            .collect())
+    }
+
+    /// Reads `[start, end)` in chunks, keeping the last `len - 1` bytes of each chunk so
+    /// a match crossing the boundary is still seen. Unreadable memory is skipped.
+    #[allow(clippy::too_many_arguments)]
+    fn scan_span(
+        &self,
+        pattern: &Pattern,
+        finder: &Finder<'_>,
+        anchor_at: usize,
+        start: usize,
+        end: usize,
+        chunk: usize,
+        matches: &mut Vec<usize>,
+    ) {
+        let overlap = pattern.len() - 1;
+        let mut buffer = Vec::with_capacity(chunk + overlap);
+        let mut cursor = start;
+
+        while cursor < end {
+            let carried = buffer.len();
+            let len = chunk.min(end - cursor);
+            buffer.resize(carried + len, 0);
+
+            let read = self.read_bytes(cursor, &mut buffer[carried..]).unwrap_or(0);
+            buffer.truncate(carried + read);
+
+            let buffer_base = cursor - carried;
+            matches.extend(pattern.find_with(finder, anchor_at, &buffer).into_iter().map(|at| buffer_base + at));
+
+            if read == len {
+                buffer.drain(..buffer.len().saturating_sub(overlap));
+                cursor += len;
+            } else {
+                buffer.clear();
+                cursor = self.skip_unreadable(cursor + read).min(end);
+            }
+        }
+    }
+
+    /// First address past the unreadable memory at `address`.
+    pub(crate) fn skip_unreadable(&self, address: usize) -> usize {
+        match self.query_region(address) {
+            Ok(region) if !region.is_readable() && region.end_address() > address => region.end_address(),
+            _ => (address | (PAGE_SIZE - 1)).saturating_add(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    #[test]
+    fn parses_ida_and_code_style() {
+        let ida = Pattern::parse("48 8B 05 ?? ? ?? ?? 48 85 C0").unwrap();
+        let escaped = Pattern::parse("\\x48\\x8B\\x05\\x??\\x??\\x??\\x??\\x48\\x85\\xC0").unwrap();
+        let code = Pattern::from_code(b"\x48\x8B\x05\x00\x00\x00\x00\x48\x85\xC0", "xxx????xxx").unwrap();
+
+        assert_eq!(ida, code);
+        assert_eq!(escaped, code);
+        assert_eq!(ida.to_string(), "48 8B 05 ?? ?? ?? ?? 48 85 C0");
+        assert_eq!(ida.anchor(), (0, &[0x48, 0x8B, 0x05][..]));
+        assert_eq!(Pattern::parse("E8 ?? 48 85 C0").unwrap().anchor(), (2, &[0x48, 0x85, 0xC0][..]));
+
+        assert!(matches!(Pattern::parse("48 8G"), Err(mm_error::InvalidPattern(_))));
+        assert!(matches!(Pattern::parse("?? ??"), Err(mm_error::InvalidPattern(_))));
+        assert!(matches!(Pattern::from_code(b"\x48", "xx"), Err(mm_error::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn finds_matches_across_chunks_and_skips_gaps() {
+        let mut data = vec![0x90u8; 0x3000];
+        data[0xFFE..0x1004].copy_from_slice(&[0x48, 0x8B, 0x05, 0x11, 0x22, 0x48]);
+        data[0x2800..0x2806].copy_from_slice(&[0x48, 0x8B, 0x05, 0x33, 0x44, 0x48]);
+
+        let mock = MockProcess::new();
+        mock.map(0x10000, data, Protection::READ);
+        mock.map(0x20000, vec![0x48, 0x8B, 0x05, 0, 0, 0x48], Protection::READ);
+        mock.map(0x30000, vec![0x48, 0x8B, 0x05, 0, 0, 0x48], Protection::NONE);
+        let mem = mmg::new(&mock);
+
+        let pattern = Pattern::parse("48 8B 05 ?? ?? 48").unwrap();
+        let (anchor_at, anchor) = pattern.anchor();
+        let mut small_chunks = Vec::new();
+        mem.scan_span(&pattern, &Finder::new(anchor), anchor_at, 0x10000, 0x40000, 0x800, &mut small_chunks);
+        assert_eq!(small_chunks, [0x10FFE, 0x12800, 0x20000]);
+
+        let all = mem.find_pattern("48 8B 05 ?? ?? 48", 0x10000..0x40000).unwrap();
+        assert_eq!(all, small_chunks);
+
+        let module = ModuleInfo::new("game.so".into(), 0x10000, 0x3000, 0);
+        assert_eq!(mem.find_pattern("48 8B 05 33", &module).unwrap(), [0x12800]);
+    }
+
+    #[test]
+    fn region_sets_merge_adjacent_readable_regions() {
+        let mock = MockProcess::new();
+        mock.map(0x1000, vec![0, 0, 0xDE, 0xAD], Protection::READ);
+        mock.map(0x1004, vec![0xBE, 0xEF], Protection::READ_EXECUTE);
+        mock.map(0x2000, vec![0xDE, 0xAD, 0xBE, 0xEF], Protection::NONE);
+        let mem = mmg::new(&mock);
+
+        let regions: ScanRange = mem.query_regions().unwrap().collect();
+        assert_eq!(regions.spans(), [(0x1000, 0x1006)]);
+        assert_eq!(mem.find_pattern("DE AD BE EF", regions).unwrap(), [0x1002]);
+    }
+
+    #[test]
+    fn resolves_rip_relative_operands() {
+        // mov rax, [rip+0x100] ; test rax, rax ; call -0x20
+        let code = [0x48, 0x8B, 0x05, 0x00, 0x01, 0x00, 0x00, 0x48, 0x85, 0xC0, 0xE8, 0xE0, 0xFF, 0xFF, 0xFF];
+        let mock = MockProcess::new();
+        mock.map(0x40_1000, code.to_vec(), Protection::READ_EXECUTE);
+        let mem = mmg::new(&mock);
+
+        assert_eq!(mem.resolve_rip(0x40_1000, 3, 7).unwrap(), 0x40_1107);
+        assert_eq!(mem.resolve_rip(0x40_100A, 1, 5).unwrap(), 0x40_0FEF);
+        assert!(mem.resolve_rip(0x40_100A, 100, 5).is_err());
+
+        let range = 0x40_1000..0x40_2000;
+        let globals = mem.find_pattern_resolved("48 8B 05 ?? ?? ?? ?? 48 85 C0", range.clone(), RipOperand::new(3, 7));
+        assert_eq!(globals.unwrap(), [0x40_1107]);
+
+        let calls = mem.find_pattern_resolved("48 85 C0 E8", range, RipOperand::new(1, 5).at(3));
+        assert_eq!(calls.unwrap(), [0x40_0FEF]);
+    }
+}
@@ -0,0 +1,167 @@
+/// Plain old data: types that can be copied in and out of another process as raw bytes.
+/// Bounds `mmg::read`/`write`, `TypeReader` and every other typed access.
+///
+/// Implemented for the integer and float primitives, raw pointers (read as plain
+/// addresses) and arrays of `Pod`. For your own `#[repr(C)]` structs, implement it by hand.
+///
+/// # Safety
+/// Every bit pattern of `size_of::<Self>()` bytes must be a valid `Self`, so no `bool`,
+/// `char`, enums, references or `NonNull`. `Self` must not contain padding either, since
+/// writes send every byte of the value. Types with validity invariants go through
+/// `CheckedPod` instead.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! pod {
+    ($($ty:ty),* $(,)?) => {
+        $(unsafe impl Pod for $ty {})*
+    };
+}
+
+pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+unsafe impl<T: 'static> Pod for *const T {}
+unsafe impl<T: 'static> Pod for *mut T {}
+
+/// A type with validity invariants, read as its `Raw` representation and then checked,
+/// e.g. `bool` (a `u8` that must be 0 or 1) or a fieldless `#[repr(u32)]` enum.
+pub trait CheckedPod: Copy {
+    type Raw: Pod;
+
+    /// `None` when `raw` is not a valid `Self`.
+    fn from_raw(raw: Self::Raw) -> Option<Self>;
+
+    fn to_raw(self) -> Self::Raw;
+}
+
+impl CheckedPod for bool {
+    type Raw = u8;
+
+    fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn to_raw(self) -> u8 {
+        u8::from(self)
+    }
+}
+
+impl CheckedPod for char {
+    type Raw = u32;
+
+    fn from_raw(raw: u32) -> Option<Self> {
+        char::from_u32(raw)
+    }
+
+    fn to_raw(self) -> u32 {
+        u32::from(self)
+    }
+}
+
+/// The bytes of `value`, as written to another process.
+#[must_use]
+#[inline]
+pub fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(std::ptr::from_ref(value).cast::<u8>(), std::mem::size_of::<T>()) }
+}
+
+/// The bytes of `value`, to be filled by a read. Any contents are a valid `T`.
+#[must_use]
+#[inline]
+pub fn bytes_of_mut<T: Pod>(value: &mut T) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(std::ptr::from_mut(value).cast::<u8>(), std::mem::size_of::<T>()) }
+}
+
+/// The bytes of `values`, to be filled by a read.
+#[must_use]
+#[inline]
+pub fn slice_bytes_mut<T: Pod>(values: &mut [T]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr().cast::<u8>(), std::mem::size_of_val(values)) }
+}
+
+/// All-zero `T`, valid for any `Pod`.
+#[must_use]
+#[inline]
+pub fn zeroed<T: Pod>() -> T {
+    unsafe { std::mem::MaybeUninit::zeroed().assume_init() }
+}
+
+/// Copies a `T` out of the start of `bytes`, which may be unaligned. `None` when too short.
+#[must_use]
+#[inline]
+pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Option<T> {
+    let bytes = bytes.get(..std::mem::size_of::<T>())?;
+    Some(unsafe { std::ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::error::mm_error;
+    use crate::memory::mmg;
+    use crate::region::Protection;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[repr(u32)]
+    enum Team {
+        Red = 1,
+        Blue = 2,
+    }
+
+    impl CheckedPod for Team {
+        type Raw = u32;
+
+        fn from_raw(raw: u32) -> Option<Self> {
+            match raw {
+                1 => Some(Self::Red),
+                2 => Some(Self::Blue),
+                _ => None,
+            }
+        }
+
+        fn to_raw(self) -> u32 {
+            self as u32
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip_unaligned() {
+        let value = [0x1122_3344u32, 0x5566_7788];
+        assert_eq!(bytes_of(&value).len(), 8);
+
+        let mut shifted = vec![0u8];
+        shifted.extend_from_slice(bytes_of(&value));
+        assert_eq!(from_bytes::<[u32; 2]>(&shifted[1..]), Some(value));
+        assert_eq!(from_bytes::<u64>(&shifted[..4]), None);
+
+        let mut target = 0u16;
+        bytes_of_mut(&mut target).copy_from_slice(&[0xEF, 0xBE]);
+        assert_eq!(target, u16::from_le_bytes([0xEF, 0xBE]));
+    }
+
+    #[test]
+    fn checked_reads_reject_invalid_bit_patterns() {
+        let mock = MockProcess::new();
+        mock.map(0x1000, vec![1, 7, 0, 0, 2, 0, 0, 0, 9, 0, 0, 0], Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        assert!(mem.read_checked::<bool>(0x1000).unwrap());
+        assert!(matches!(
+            mem.read_checked::<bool>(0x1001),
+            Err(mm_error::InvalidValue { address: 0x1001, type_name: "bool" })
+        ));
+        assert_eq!(mem.read_checked::<Team>(0x1004).unwrap(), Team::Blue);
+        assert!(matches!(mem.read_checked::<Team>(0x1008), Err(mm_error::InvalidValue { .. })));
+
+        mem.write_checked(0x1008, Team::Red).unwrap();
+        mem.write_checked(0x1001, false).unwrap();
+        assert_eq!(mem.read_checked::<Team>(0x1008).unwrap(), Team::Red);
+        assert!(!mem.read_checked::<bool>(0x1001).unwrap());
+        assert_eq!(mem.read_checked::<char>(0x1008).unwrap(), '\u{1}');
+    }
+}
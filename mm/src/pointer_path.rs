@@ -0,0 +1,198 @@
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::{mmg, PointerTrace};
+use crate::pod::Pod;
+
+/// A static pointer chain: `module + offsets[0]` is dereferenced, then every following
+/// offset is added to the pointer just read, with the last one added but not dereferenced.
+/// This is exactly what `read_pointer_chain(module_base, &offsets)` walks.
+///
+/// The canonical text form is `"game.so"+0x1A2B30 -> 0x18 -> 0x10`: quoted module name,
+/// upper-case hex offsets, ` -> ` between hops. Offsets that only make sense as negative
+/// (above `isize::MAX`) are written as `-0x8`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointerPath {
+    pub module: String,
+    pub offsets: Vec<usize>,
+}
+
+impl PointerPath {
+    #[must_use]
+    pub const fn new(module: String, offsets: Vec<usize>) -> Self {
+        Self { module, offsets }
+    }
+
+    /// Parses the canonical form. Also accepts an unquoted module name without
+    /// `"`, `+` or whitespace, decimal offsets and any amount of whitespace around `->`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = |what: &str| mm_error::InvalidPointerPath(format!("{} in \"{}\"", what, text));
+        let text = text.trim();
+
+        let (module, rest) = if let Some(quoted) = text.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| invalid("unterminated module name"))?;
+            (&quoted[..end], quoted[end + 1..].trim_start())
+        } else {
+            let end = text.find(|c: char| c == '+' || c.is_whitespace()).unwrap_or(text.len());
+            (&text[..end], text[end..].trim_start())
+        };
+
+        if module.is_empty() {
+            return Err(invalid("missing module name"));
+        }
+
+        let mut offsets = Vec::new();
+        if !rest.is_empty() {
+            let rest = rest.strip_prefix('+').ok_or_else(|| invalid("expected '+' after the module"))?;
+            for hop in rest.split("->") {
+                offsets.push(parse_offset(hop.trim()).ok_or_else(|| invalid(hop.trim()))?);
+            }
+        }
+
+        Ok(Self::new(module.to_string(), offsets))
+    }
+
+    /// Number of dereferences along the path.
+    #[must_use]
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Final address the path points at in the process behind `mem`.
+    pub fn resolve<B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>) -> Result<usize> {
+        let base = mem.get_module_base(&self.module)?;
+        mem.read_pointer_chain(base, &self.offsets)
+    }
+
+    /// `resolve`, keeping every hop. See `mmg::trace_pointer_chain`.
+    pub fn trace<B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>) -> Result<PointerTrace> {
+        let base = mem.get_module_base(&self.module)?;
+        mem.trace_pointer_chain(base, &self.offsets)
+    }
+
+    /// Resolves the path and reads a `T` at the final address.
+    pub fn read<T: Pod, B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>) -> Result<T> {
+        mem.read::<T>(self.resolve(mem)?)
+    }
+
+    /// Resolves the path and writes `value` at the final address.
+    pub fn write<T: Pod, B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>, value: &T) -> Result<usize> {
+        mem.write(self.resolve(mem)?, value)
+    }
+}
+
+fn parse_offset(text: &str) -> Option<usize> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits.trim_start()),
+        None => (false, text),
+    };
+
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+
+    Some(if negative { value.wrapping_neg() } else { value })
+}
+
+impl std::str::FromStr for PointerPath {
+    type Err = mm_error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for PointerPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.module)?;
+
+        for (index, &offset) in self.offsets.iter().enumerate() {
+            f.write_str(if index == 0 { "+" } else { " -> " })?;
+            if offset > isize::MAX as usize {
+                write!(f, "-0x{:X}", offset.wrapping_neg())?;
+            } else {
+                write!(f, "0x{:X}", offset)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Serialized as the canonical string, so paths read naturally in config files.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PointerPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PointerPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::module::ModuleInfo;
+    use crate::region::Protection;
+
+    #[test]
+    fn parses_and_prints_the_canonical_form() {
+        let path = PointerPath::parse("\"game.so\"+0x1A2B30 -> 0x18 -> 0x10").unwrap();
+        assert_eq!(path, PointerPath::new("game.so".into(), vec![0x1A_2B30, 0x18, 0x10]));
+        assert_eq!(path.to_string(), "\"game.so\"+0x1A2B30 -> 0x18 -> 0x10");
+
+        let loose: PointerPath = "  \"Game Client.exe\"+0x10->24 ->  -0x8".parse().unwrap();
+        assert_eq!(loose.offsets, [0x10usize, 24, 8usize.wrapping_neg()]);
+        assert_eq!(loose.to_string(), "\"Game Client.exe\"+0x10 -> 0x18 -> -0x8");
+        assert_eq!(PointerPath::parse("libc-2.31.so+0x20").unwrap().to_string(), "\"libc-2.31.so\"+0x20");
+        assert_eq!(PointerPath::parse("\"libc.so.6\"").unwrap().offsets, Vec::<usize>::new());
+
+        for bad in ["", "\"game.so", "\"game.so\" 0x10", "game.so+0x10 -> zz", "\"\"+0x1"] {
+            assert!(matches!(PointerPath::parse(bad), Err(mm_error::InvalidPointerPath(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn resolves_and_reads_through_the_module_base() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x40_0000, 0x1000, Protection::READ_WRITE);
+        mock.map_zeroed(0x100_0000, 0x1000, Protection::READ_WRITE);
+        mock.add_module(ModuleInfo::new("game.so".into(), 0x40_0000, 0x1000, 0));
+        let mem = mmg::new(&mock);
+        mem.write::<usize>(0x40_0100, &0x100_0000).unwrap();
+        mem.write::<f32>(0x100_0018, &42.5).unwrap();
+
+        let path: PointerPath = "\"GAME.so\"+0x100 -> 0x18".parse().unwrap();
+        assert_eq!(path.resolve(&mem).unwrap(), 0x100_0018);
+        assert_eq!(path.read::<f32, _>(&mem).unwrap(), 42.5);
+
+        path.write(&mem, &7.0f32).unwrap();
+        assert_eq!(mem.read::<f32>(0x100_0018).unwrap(), 7.0);
+
+        let trace = path.trace(&mem).unwrap();
+        assert_eq!(trace.hops.iter().map(|h| (h.address, h.value)).collect::<Vec<_>>(), [(0x40_0100, 0x100_0000)]);
+        assert_eq!(trace.address, 0x100_0018);
+
+        let missing = PointerPath::parse("other.so+0x0").unwrap();
+        assert!(matches!(missing.resolve(&mem), Err(mm_error::ModuleNotFound(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_through_the_canonical_string() {
+        let path = PointerPath::parse("\"game.so\"+0x1A2B30 -> 0x18 -> -0x10").unwrap();
+        let json = serde_json::to_string(&path).unwrap();
+
+        assert_eq!(json, r#""\"game.so\"+0x1A2B30 -> 0x18 -> -0x10""#);
+        assert_eq!(serde_json::from_str::<PointerPath>(&json).unwrap(), path);
+        assert!(serde_json::from_str::<PointerPath>(r#""game.so+""#).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+
+use crate::backend::MemoryBackend;
+use crate::error::Result;
+use crate::memory::mmg;
+use crate::module::ModuleInfo;
+use crate::pattern::SCAN_CHUNK;
+use crate::pointer_path::PointerPath;
+
+const POINTER_SIZE: usize = std::mem::size_of::<usize>();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerScanOptions {
+    /// Maximum number of dereferences in a path.
+    pub max_depth: usize,
+    /// Largest offset added to a pointer at any hop.
+    pub max_offset: usize,
+    /// Step between candidate pointer locations.
+    pub alignment: usize,
+    /// Most paths returned, the shallowest first.
+    pub max_results: usize,
+    /// Most locations carried from one depth to the next; the rest of a wide level is dropped.
+    pub max_frontier: usize,
+}
+
+impl Default for PointerScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            max_offset: 0x1000,
+            alignment: POINTER_SIZE,
+            max_results: 10_000,
+            max_frontier: 100_000,
+        }
+    }
+}
+
+/// Every aligned pointer-sized value in readable memory that points back into readable
+/// memory, as `(value, location)` sorted by value. Building it is the expensive part of a
+/// pointer scan, so keep it around to search several targets in one snapshot of the process.
+/// Pages that fail to read are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct PointerMap {
+    pointers: Vec<(usize, usize)>,
+}
+
+impl PointerMap {
+    pub fn build<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, alignment: usize) -> Result<Self> {
+        let alignment = alignment.max(1);
+        let readable: Vec<(usize, usize)> = mem
+            .query_regions()?
+            .filter(|r| r.is_readable())
+            .map(|r| (r.base, r.end_address()))
+            .collect();
+        let points_into_readable = |value: usize| {
+            let index = readable.partition_point(|&(_, end)| end <= value);
+            readable.get(index).is_some_and(|&(start, _)| value >= start)
+        };
+
+        // Chunks keep their last `POINTER_SIZE - 1` bytes so a pointer crossing the
+        // boundary is still seen, as in `scan_span`.
+        let overlap = POINTER_SIZE - 1;
+        let mut buffer = Vec::with_capacity(SCAN_CHUNK + overlap);
+        let mut pointers = Vec::new();
+        for &(start, end) in &readable {
+            let mut cursor = start;
+            buffer.clear();
+
+            while cursor < end {
+                let carried = buffer.len();
+                let len = SCAN_CHUNK.min(end - cursor);
+                buffer.resize(carried + len, 0);
+
+                let read = mem.read_bytes(cursor, &mut buffer[carried..]).unwrap_or(0);
+                buffer.truncate(carried + read);
+
+                let buffer_base = cursor - carried;
+                let first = buffer_base.next_multiple_of(alignment) - buffer_base;
+                for offset in (first..buffer.len().saturating_sub(overlap)).step_by(alignment) {
+                    let value = usize::from_ne_bytes(buffer[offset..offset + POINTER_SIZE].try_into().unwrap());
+                    if value != 0 && points_into_readable(value) {
+                        pointers.push((value, buffer_base + offset));
+                    }
+                }
+
+                if read == len {
+                    buffer.drain(..buffer.len().saturating_sub(overlap));
+                    cursor += len;
+                } else {
+                    buffer.clear();
+                    cursor = mem.skip_unreadable(cursor + read).min(end);
+                }
+            }
+        }
+
+        pointers.sort_unstable();
+        Ok(Self { pointers })
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pointers.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pointers.is_empty()
+    }
+
+    /// Locations holding a pointer into `[address - max_offset, address]`, with the offset
+    /// from that pointer to `address`.
+    fn referrers(&self, address: usize, max_offset: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let low = address.saturating_sub(max_offset);
+        let start = self.pointers.partition_point(|&(value, _)| value < low);
+
+        self.pointers[start..]
+            .iter()
+            .take_while(move |&&(value, _)| value <= address)
+            .map(move |&(value, location)| (location, address - value))
+    }
+
+    /// Walks backwards from `target` and returns every path rooted inside one of
+    /// `modules`, shortest first. A path stops at the first static location it reaches.
+    ///
+    /// The search goes breadth first, one depth at a time, so when `max_results` cuts it
+    /// short the paths kept are the shallowest ones. Each location is walked through at most
+    /// once and each depth keeps at most `max_frontier` of them, which bounds the search on
+    /// self-referential or heavily shared data.
+    #[must_use]
+    pub fn find_paths(&self, target: usize, modules: &[ModuleInfo], options: &PointerScanOptions) -> Vec<PointerPath> {
+        let mut steps = vec![Step {
+            address: target,
+            offset: 0,
+            parent: None,
+        }];
+        let mut level = vec![0];
+        let mut visited = HashSet::from([target]);
+        let mut paths = Vec::new();
+
+        'search: for depth in 1..=options.max_depth {
+            let mut next = Vec::new();
+
+            for &index in &level {
+                for (location, offset) in self.referrers(steps[index].address, options.max_offset) {
+                    if let Some(module) = modules.iter().find(|m| m.contains_address(location)) {
+                        let mut offsets = vec![location - module.addy, offset];
+                        offsets.extend(trail(&steps, index).map(|step| step.offset));
+                        offsets.pop();
+                        paths.push(PointerPath::new(module.name.clone(), offsets));
+                        if paths.len() >= options.max_results {
+                            break 'search;
+                        }
+                    } else if depth < options.max_depth
+                        && next.len() < options.max_frontier
+                        && visited.insert(location)
+                    {
+                        steps.push(Step {
+                            address: location,
+                            offset,
+                            parent: Some(index),
+                        });
+                        next.push(steps.len() - 1);
+                    }
+                }
+            }
+
+            level = next;
+        }
+
+        paths.sort_by(|a, b| a.depth().cmp(&b.depth()).then_with(|| a.cmp(b)));
+        paths.truncate(options.max_results);
+        paths
+    }
+}
+
+/// A location to find pointers to: `offset` past the pointer stored at `address` leads
+/// on to the parent step, the root being the scan target itself.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    address: usize,
+    offset: usize,
+    parent: Option<usize>,
+}
+
+/// `steps[index]` and its ancestors, up to and including the target.
+fn trail(steps: &[Step], index: usize) -> impl Iterator<Item = &Step> {
+    std::iter::successors(Some(&steps[index]), |step| step.parent.map(|parent| &steps[parent]))
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// Builds a `PointerMap` and searches it for paths to `target`, rooted in the
+    /// process's modules.
+    pub fn pointer_scan(&self, target: usize, options: &PointerScanOptions) -> Result<Vec<PointerPath>> {
+        let modules = self.enumerate_modules()?;
+        let map = PointerMap::build(self, options.alignment)?;
+        Ok(map.find_paths(target, &modules, options))
+    }
+
+    /// Keeps the paths that still lead to `target`, e.g. after restarting the process
+    /// and finding the value again. Paths that no longer resolve are dropped.
+    #[must_use]
+    pub fn rescan_pointer_paths(&self, paths: &[PointerPath], target: usize) -> Vec<PointerPath> {
+        paths
+            .iter()
+            .filter(|path| path.resolve(self).is_ok_and(|address| address == target))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::{MockFault, MockProcess};
+    use crate::region::Protection;
+
+    /// `game.so`+0x100 -> node (+0x18) -> player (+0x10) -> health
+    fn process(node: usize, player: usize) -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x40_0000, 0x1000, Protection::READ_WRITE);
+        mock.add_module(ModuleInfo::new("game.so".into(), 0x40_0000, 0x1000, 0));
+        mock.map_zeroed(node & !0xFFF, 0x1000, Protection::READ_WRITE);
+        mock.map_zeroed(player & !0xFFF, 0x1000, Protection::READ_WRITE);
+
+        let mem = mmg::new(&mock);
+        mem.write::<usize>(0x40_0100, &node).unwrap();
+        mem.write::<usize>(node + 0x18, &player).unwrap();
+        mem.write::<u32>(player + 0x10, &100).unwrap();
+        mock
+    }
+
+    #[test]
+    fn finds_static_paths_usable_by_read_pointer_chain() {
+        let mock = process(0x100_0040, 0x200_0000);
+        let mem = mmg::new(&mock);
+        let target = 0x200_0010;
+
+        let options = PointerScanOptions {
+            max_offset: 0x20,
+            ..PointerScanOptions::default()
+        };
+        let paths = mem.pointer_scan(target, &options).unwrap();
+
+        let expected = PointerPath::new("game.so".into(), vec![0x100, 0x18, 0x10]);
+        assert_eq!(paths, std::slice::from_ref(&expected));
+        assert_eq!(mem.read_pointer_chain(0x40_0000, &expected.offsets).unwrap(), target);
+
+        let shallow = PointerScanOptions {
+            max_depth: 1,
+            ..options.clone()
+        };
+        assert!(mem.pointer_scan(target, &shallow).unwrap().is_empty());
+
+        let tight = PointerScanOptions { max_offset: 0x10, ..options };
+        assert!(mem.pointer_scan(target, &tight).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rescan_keeps_paths_that_survive_a_restart() {
+        let first = process(0x100_0040, 0x200_0000);
+        mmg::new(&first).write::<usize>(0x40_0300, &0x200_0000).unwrap();
+        let options = PointerScanOptions {
+            max_offset: 0x40,
+            ..PointerScanOptions::default()
+        };
+        let paths = mmg::new(&first).pointer_scan(0x200_0010, &options).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        let restarted = process(0x300_0080, 0x500_0100);
+        let survivors = mmg::new(&restarted).rescan_pointer_paths(&paths, 0x500_0110);
+        assert_eq!(survivors, [PointerPath::new("game.so".into(), vec![0x100, 0x18, 0x10])]);
+    }
+
+    #[test]
+    fn result_cap_keeps_the_shallowest_paths() {
+        let mock = process(0x100_0040, 0x200_0000);
+        let mem = mmg::new(&mock);
+        // found after the two-hop path in address order, but shallower
+        mem.write::<usize>(0x40_0200, &0x200_0008).unwrap();
+        // self-referencing slot next to the node: one more real path, but not walked in a loop
+        mem.write::<usize>(0x100_0060, &0x100_0040).unwrap();
+
+        let options = PointerScanOptions {
+            max_depth: 12,
+            max_offset: 0x20,
+            max_results: 1,
+            ..PointerScanOptions::default()
+        };
+        let paths = mem.pointer_scan(0x200_0010, &options).unwrap();
+        assert_eq!(paths, [PointerPath::new("game.so".into(), vec![0x200, 0x8])]);
+
+        let narrow = PointerScanOptions { max_results: 10, max_frontier: 0, ..options.clone() };
+        assert_eq!(mem.pointer_scan(0x200_0010, &narrow).unwrap(), paths);
+
+        let all = PointerScanOptions { max_results: 10, ..options };
+        let paths = mem.pointer_scan(0x200_0010, &all).unwrap();
+        assert_eq!(
+            paths[1..],
+            [
+                PointerPath::new("game.so".into(), vec![0x100, 0x18, 0x10]),
+                PointerPath::new("game.so".into(), vec![0x100, 0x20, 0x18, 0x10]),
+            ]
+        );
+    }
+
+    #[test]
+    fn map_sees_pointers_across_chunks_and_past_faults() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x100_0000, SCAN_CHUNK + 0x4000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+        // chunks restart at the page after the fault, so this one straddles the first boundary
+        let straddling = 0x100_3000 + SCAN_CHUNK - 4;
+        mem.write::<usize>(0x100_3000, &0x100_0020).unwrap();
+        mem.write::<usize>(straddling, &0x100_0010).unwrap();
+        mock.inject_fault(0x100_2000..0x100_2008, MockFault::PartialCopy);
+
+        let map = PointerMap::build(&mem, 4).unwrap();
+        assert_eq!(map.pointers, [(0x100_0010, straddling), (0x100_0020, 0x100_3000)]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use crate::region::{MemoryRegion, Protection, RegionKind};
+
+#[derive(Debug, Clone)]
+pub(crate) struct MapsEntry {
+    pub start: usize,
+    pub end: usize,
+    pub perms: [u8; 4],
+    pub offset: u64,
+    pub path: Option<String>,
+}
+
+impl MapsEntry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, ' ');
+        let (start, end) = fields.next()?.split_once('-')?;
+        let perms = fields.next()?.as_bytes().get(..4)?.try_into().ok()?;
+        let offset = u64::from_str_radix(fields.next()?, 16).ok()?;
+        let _dev = fields.next()?;
+        let _inode = fields.next()?;
+        let path = fields.next().map(str::trim_start).filter(|p| !p.is_empty()).map(str::to_string);
+
+        Some(Self {
+            start: usize::from_str_radix(start, 16).ok()?,
+            end: usize::from_str_radix(end, 16).ok()?,
+            perms,
+            offset,
+            path,
+        })
+    }
+
+    pub fn backing_file(&self) -> Option<&str> {
+        self.path.as_deref().filter(|p| p.starts_with('/'))
+    }
+
+    pub fn protection(&self) -> Protection {
+        let mut protection = Protection::NONE;
+        if self.perms[0] == b'r' {
+            protection = protection | Protection::READ;
+        }
+        if self.perms[1] == b'w' {
+            protection = protection | Protection::WRITE;
+        }
+        if self.perms[2] == b'x' {
+            protection = protection | Protection::EXECUTE;
+        }
+        protection
+    }
+}
+
+/// `pid` is a numeric pid or `"self"`.
+pub(crate) fn read_maps(pid: &str) -> std::io::Result<Vec<MapsEntry>> {
+    let maps = std::fs::read_to_string(format!("/proc/{}/maps", pid))?;
+    Ok(maps.lines().filter_map(MapsEntry::parse).collect())
+}
+
+/// Backing files with at least one executable mapping, in one pass over `maps`.
+fn image_files(maps: &[MapsEntry]) -> HashSet<&str> {
+    maps.iter().filter(|e| e.perms[2] == b'x').filter_map(MapsEntry::backing_file).collect()
+}
+
+/// Files with an executable mapping are treated as loaded images, other file
+/// mappings as `Mapped`, and anonymous or pseudo (`[heap]`, `[stack]`) mappings as `Private`.
+fn entry_region(images: &HashSet<&str>, entry: &MapsEntry) -> MemoryRegion {
+    let mut region = MemoryRegion::new(entry.start, entry.end - entry.start, entry.protection());
+
+    region.kind = match entry.backing_file() {
+        Some(file) if images.contains(file) => RegionKind::Image,
+        Some(_) => RegionKind::Mapped,
+        None => RegionKind::Private,
+    };
+    region.mapped_file = entry.backing_file().map(str::to_string);
+
+    region
+}
+
+/// Region containing `address`, or the unmapped gap around it.
+pub(crate) fn region_at(maps: &[MapsEntry], address: usize) -> MemoryRegion {
+    if let Some(entry) = maps.iter().find(|e| address >= e.start && address < e.end) {
+        return entry_region(&image_files(maps), entry);
+    }
+
+    let gap_start = maps.iter().map(|e| e.end).filter(|&end| end <= address).max().unwrap_or(0);
+    let gap_end = maps.iter().map(|e| e.start).filter(|&start| start > address).min().unwrap_or(usize::MAX);
+
+    MemoryRegion::free(gap_start, gap_end - gap_start)
+}
+
+/// Every mapping in address order, with the unmapped gaps between them as free regions.
+pub(crate) fn regions(maps: &[MapsEntry]) -> Vec<MemoryRegion> {
+    let mut result = Vec::with_capacity(maps.len() * 2 + 1);
+    let images = image_files(maps);
+    let mut cursor = 0;
+
+    for entry in maps {
+        if entry.start > cursor {
+            result.push(MemoryRegion::free(cursor, entry.start - cursor));
+        }
+        result.push(entry_region(&images, entry));
+        cursor = entry.end;
+    }
+
+    if cursor < usize::MAX {
+        result.push(MemoryRegion::free(cursor, usize::MAX - cursor));
+    }
+
+    result
+}
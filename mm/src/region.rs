@@ -0,0 +1,225 @@
+const PAGE_NOACCESS: u32 = 0x01;
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_WRITECOPY: u32 = 0x08;
+const PAGE_EXECUTE: u32 = 0x10;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+const PAGE_GUARD: u32 = 0x100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Protection(u8);
+
+impl Protection {
+    pub const NONE: Self = Self(0);
+    pub const READ: Self = Self(1 << 0);
+    pub const WRITE: Self = Self(1 << 1);
+    pub const EXECUTE: Self = Self(1 << 2);
+    pub const GUARD: Self = Self(1 << 3);
+
+    pub const READ_WRITE: Self = Self(Self::READ.0 | Self::WRITE.0);
+    pub const READ_EXECUTE: Self = Self(Self::READ.0 | Self::EXECUTE.0);
+    pub const READ_WRITE_EXECUTE: Self = Self(Self::READ.0 | Self::WRITE.0 | Self::EXECUTE.0);
+
+    #[must_use]
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_readable(self) -> bool {
+        self.contains(Self::READ) && !self.contains(Self::GUARD)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_writable(self) -> bool {
+        self.contains(Self::WRITE) && !self.contains(Self::GUARD)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_executable(self) -> bool {
+        self.contains(Self::EXECUTE)
+    }
+
+    /// From Windows `PAGE_*` flags, as returned by `VirtualQueryEx` or stored in minidumps.
+    #[must_use]
+    pub const fn from_page_flags(flags: u32) -> Self {
+        let base = match flags & 0xFF {
+            PAGE_READONLY => Self::READ,
+            PAGE_READWRITE | PAGE_WRITECOPY => Self::READ_WRITE,
+            PAGE_EXECUTE => Self::EXECUTE,
+            PAGE_EXECUTE_READ => Self::READ_EXECUTE,
+            PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY => Self::READ_WRITE_EXECUTE,
+            _ => Self::NONE,
+        };
+
+        if flags & PAGE_GUARD != 0 {
+            base.union(Self::GUARD)
+        } else {
+            base
+        }
+    }
+
+    #[must_use]
+    pub const fn to_page_flags(self) -> u32 {
+        let read = self.contains(Self::READ);
+        let write = self.contains(Self::WRITE);
+        let execute = self.contains(Self::EXECUTE);
+
+        let base = match (read || write, write, execute) {
+            (_, true, true) => PAGE_EXECUTE_READWRITE,
+            (true, false, true) => PAGE_EXECUTE_READ,
+            (false, false, true) => PAGE_EXECUTE,
+            (_, true, false) => PAGE_READWRITE,
+            (true, false, false) => PAGE_READONLY,
+            (false, false, false) => PAGE_NOACCESS,
+        };
+
+        if self.contains(Self::GUARD) {
+            base | PAGE_GUARD
+        } else {
+            base
+        }
+    }
+}
+
+impl std::ops::BitOr for Protection {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl std::fmt::Display for Protection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(
+            f,
+            "{}{}{}{}",
+            flag(self.contains(Self::READ), 'r'),
+            flag(self.contains(Self::WRITE), 'w'),
+            flag(self.contains(Self::EXECUTE), 'x'),
+            flag(self.contains(Self::GUARD), 'g'),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionState {
+    Commit,
+    Reserve,
+    Free,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    Image,
+    Mapped,
+    Private,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub base: usize,
+    pub size: usize,
+    pub protection: Protection,
+    pub state: RegionState,
+    pub kind: RegionKind,
+    pub mapped_file: Option<String>,
+}
+
+impl MemoryRegion {
+    /// A committed region of unknown kind; fill in `kind`/`mapped_file` where the backend knows them.
+    #[must_use]
+    pub const fn new(base: usize, size: usize, protection: Protection) -> Self {
+        Self {
+            base,
+            size,
+            protection,
+            state: RegionState::Commit,
+            kind: RegionKind::Unknown,
+            mapped_file: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn free(base: usize, size: usize) -> Self {
+        Self {
+            base,
+            size,
+            protection: Protection::NONE,
+            state: RegionState::Free,
+            kind: RegionKind::Unknown,
+            mapped_file: None,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn end_address(&self) -> usize {
+        self.base + self.size
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn contains_address(&self, address: usize) -> bool {
+        address >= self.base && address < self.end_address()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_committed(&self) -> bool {
+        matches!(self.state, RegionState::Commit)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_readable(&self) -> bool {
+        self.is_committed() && self.protection.is_readable()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_writable(&self) -> bool {
+        self.is_committed() && self.protection.is_writable()
+    }
+}
+
+impl std::fmt::Display for MemoryRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "0x{:016X}-0x{:016X} {} {:?} {:?}",
+            self.base,
+            self.end_address(),
+            self.protection,
+            self.state,
+            self.kind
+        )?;
+
+        if let Some(file) = &self.mapped_file {
+            write!(f, " {}", file)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+use crate::pod::{from_bytes, Pod};
+
+/// Most `#[pointer]` hops `RemoteStruct::read_from` follows, so a cyclic or corrupt
+/// structure fails with `PointerDepthExceeded` instead of recursing without end.
+pub const MAX_POINTER_DEPTH: usize = 64;
+
+/// A target-process structure decoded from one read of its span. Usually derived with
+/// `#[derive(RemoteStruct)]` (the `derive` feature) from offset-annotated fields.
+pub trait RemoteStruct: Sized {
+    /// Bytes from the struct's address to the end of its last field.
+    const SIZE: usize;
+
+    /// Decodes the struct from `bytes`, its span as read from `address`. Pointer fields
+    /// are followed through `mem` with `depth - 1`; a non-null pointer met at depth 0 is
+    /// `PointerDepthExceeded`.
+    fn decode<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, address: usize, bytes: &[u8], depth: usize) -> Result<Self>;
+
+    /// Reads the whole span in one `read_bytes` and decodes it, following pointers at most
+    /// `MAX_POINTER_DEPTH` deep.
+    #[inline]
+    fn read_from<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, address: usize) -> Result<Self> {
+        Self::read_with_depth(mem, address, MAX_POINTER_DEPTH)
+    }
+
+    /// `read_from` with its own pointer depth budget.
+    fn read_with_depth<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, address: usize, depth: usize) -> Result<Self> {
+        let mut bytes = vec![0u8; Self::SIZE];
+        let read = mem.read_bytes(address, &mut bytes)?;
+
+        if read != Self::SIZE {
+            return Err(mm_error::InvalidBufferSize {
+                expected: Self::SIZE,
+                actual: read,
+            });
+        }
+
+        Self::decode(mem, address, &bytes, depth)
+    }
+}
+
+/// Lets a `#[pointer]` field point at its own struct type, e.g. `next: Option<Box<Node>>`.
+/// Pointers are followed eagerly, within the depth budget of the outermost read.
+impl<T: RemoteStruct> RemoteStruct for Box<T> {
+    const SIZE: usize = T::SIZE;
+
+    fn decode<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, address: usize, bytes: &[u8], depth: usize) -> Result<Self> {
+        T::decode(mem, address, bytes, depth).map(Box::new)
+    }
+}
+
+/// Copies a `T` out of `bytes` at `offset`, the way `mmg::read` copies it out of the process.
+pub fn decode_field<T: Pod>(bytes: &[u8], offset: usize) -> Result<T> {
+    bytes.get(offset..).and_then(from_bytes).ok_or(mm_error::InvalidBufferSize {
+        expected: offset + std::mem::size_of::<T>(),
+        actual: bytes.len(),
+    })
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// See `RemoteStruct::read_from`.
+    #[inline]
+    pub fn read_struct<T: RemoteStruct>(&self, address: usize) -> Result<T> {
+        T::read_from(self, address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+    use mm_derive::RemoteStruct;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, RemoteStruct)]
+    struct Vec3 {
+        #[offset(0x0)]
+        x: f32,
+        #[offset(0x4)]
+        y: f32,
+        #[offset(0x8)]
+        z: f32,
+    }
+
+    #[derive(Debug, PartialEq, RemoteStruct)]
+    #[remote(size = 0x10)]
+    struct Slot {
+        #[offset(0x0)]
+        id: u32,
+        #[offset(0x8)]
+        count: u16,
+    }
+
+    #[derive(Debug, PartialEq, RemoteStruct)]
+    struct Player {
+        #[offset(0x10)]
+        health: u32,
+        #[offset(0x18)]
+        #[nested]
+        position: Vec3,
+        #[offset(0x28)]
+        ammo: [u16; 4],
+        #[offset(0x30)]
+        #[nested]
+        slots: [Slot; 2],
+        #[offset(0x50)]
+        #[pointer]
+        target: Option<Box<Player>>,
+    }
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+
+        let mem = mmg::new(&mock);
+        mem.write::<u32>(0x1010, &100).unwrap();
+        mem.write::<[f32; 3]>(0x1018, &[1.0, 2.0, 3.0]).unwrap();
+        mem.write::<[u16; 4]>(0x1028, &[1, 2, 3, 4]).unwrap();
+        mem.write::<u32>(0x1030, &7).unwrap();
+        mem.write::<u16>(0x1038, &9).unwrap();
+        mem.write::<u32>(0x1040, &8).unwrap();
+        mem.write::<usize>(0x1050, &0x1800).unwrap();
+        mem.write::<u32>(0x1810, &55).unwrap();
+        mock
+    }
+
+    #[test]
+    fn derived_struct_reads_its_span_in_one_go() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        assert_eq!(<Vec3 as RemoteStruct>::SIZE, 0xC);
+        assert_eq!(<Slot as RemoteStruct>::SIZE, 0x10);
+        assert_eq!(<Player as RemoteStruct>::SIZE, 0x58);
+
+        let player: Player = mem.read_struct(0x1000).unwrap();
+        assert_eq!(player.health, 100);
+        assert_eq!(player.position, Vec3 { x: 1.0, y: 2.0, z: 3.0 });
+        assert_eq!(player.ammo, [1, 2, 3, 4]);
+        assert_eq!(player.slots, [Slot { id: 7, count: 9 }, Slot { id: 8, count: 0 }]);
+
+        let target = player.target.unwrap();
+        assert_eq!(target.health, 55);
+        assert!(target.target.is_none());
+
+        let reads: Vec<_> = mock.reads().iter().map(|r| (r.address, r.len)).collect();
+        assert_eq!(reads, [(0x1000, 0x58), (0x1800, 0x58)]);
+    }
+
+    #[test]
+    fn field_writers_use_the_declared_offsets() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        assert_eq!(Player::health_address(0x1000), 0x1010);
+        assert_eq!(Player::slots_address(0x1000), 0x1030);
+
+        Player::write_health(&mem, 0x1000, &1).unwrap();
+        Player::write_target(&mem, 0x1000, 0).unwrap();
+        Vec3::write_y(&mem, Player::position_address(0x1000), &-2.0).unwrap();
+
+        let player: Player = mem.read_struct(0x1000).unwrap();
+        assert_eq!((player.health, player.position.y, player.target), (1, -2.0, None));
+        assert!(matches!(mem.read_struct::<Player>(0x1FC0), Err(mm_error::InvalidBufferSize { .. })));
+    }
+
+    #[test]
+    fn cyclic_pointers_run_out_of_depth() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        Player::write_target(&mem, 0x1800, 0x1000).unwrap();
+
+        assert!(matches!(mem.read_struct::<Player>(0x1000), Err(mm_error::PointerDepthExceeded(_))));
+        let reads = mock.reads().len();
+        assert!(reads <= MAX_POINTER_DEPTH + 1);
+
+        Player::write_target(&mem, 0x1800, 0).unwrap();
+        assert!(matches!(Player::read_with_depth(&mem, 0x1000, 0), Err(mm_error::PointerDepthExceeded(0x1800))));
+        assert!(Player::read_with_depth(&mem, 0x1000, 1).is_ok());
+    }
+}
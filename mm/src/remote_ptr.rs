@@ -0,0 +1,453 @@
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::{mmg, TypeReader};
+use crate::pod::{slice_bytes_mut, zeroed, Pod};
+
+/// How a pointer is laid out in the target: `u32` for 32-bit processes and dumps,
+/// `u64` for 64-bit ones, `usize` for "same as this process".
+pub trait PointerWidth: Pod + Eq + Hash + Debug {
+    fn to_address(self) -> usize;
+
+    /// Truncates `address` to the width.
+    fn from_address(address: usize) -> Self;
+}
+
+impl PointerWidth for u32 {
+    #[inline]
+    fn to_address(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_address(address: usize) -> Self {
+        address as u32
+    }
+}
+
+impl PointerWidth for u64 {
+    #[inline]
+    fn to_address(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_address(address: usize) -> Self {
+        address as u64
+    }
+}
+
+impl PointerWidth for usize {
+    #[inline]
+    fn to_address(self) -> usize {
+        self
+    }
+
+    #[inline]
+    fn from_address(address: usize) -> Self {
+        address
+    }
+}
+
+/// Typed address of a `T` in the target, laid out exactly like the target's own pointer
+/// of width `P`. It is `Pod` itself, so it can be read out of remote memory (or be a field
+/// of a `RemoteStruct`) and followed from there.
+#[repr(transparent)]
+pub struct RemotePtr<T, P: PointerWidth = usize> {
+    address: P,
+    _target: PhantomData<fn() -> T>,
+}
+
+pub type RemotePtr32<T> = RemotePtr<T, u32>;
+pub type RemotePtr64<T> = RemotePtr<T, u64>;
+
+unsafe impl<T: 'static, P: PointerWidth> Pod for RemotePtr<T, P> {}
+
+impl<T, P: PointerWidth> RemotePtr<T, P> {
+    /// Size of the pointer in the target.
+    pub const WIDTH: usize = std::mem::size_of::<P>();
+
+    #[must_use]
+    #[inline]
+    pub fn new(address: usize) -> Self {
+        Self {
+            address: P::from_address(address),
+            _target: PhantomData,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn null() -> Self {
+        Self::new(0)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn address(self) -> usize {
+        self.address.to_address()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_null(self) -> bool {
+        self.address() == 0
+    }
+
+    /// Pointer `count` elements of `T` away, wrapping like `pointer::wrapping_offset`.
+    #[must_use]
+    #[inline]
+    pub fn offset(self, count: isize) -> Self {
+        let bytes = count.wrapping_mul(std::mem::size_of::<T>() as isize);
+        self.byte_offset(bytes)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn byte_offset(self, bytes: isize) -> Self {
+        Self::new(self.address().wrapping_add_signed(bytes))
+    }
+
+    /// Same address, other target type.
+    #[must_use]
+    #[inline]
+    pub fn cast<U>(self) -> RemotePtr<U, P> {
+        RemotePtr::new(self.address())
+    }
+
+    /// `len` elements starting here.
+    #[must_use]
+    #[inline]
+    pub fn slice(self, len: usize) -> RemoteSlice<T> {
+        RemoteSlice::new(self.address(), len)
+    }
+
+    fn non_null(self) -> Result<usize> {
+        if self.is_null() {
+            return Err(mm_error::NullPointer);
+        }
+
+        Ok(self.address())
+    }
+}
+
+impl<T: Pod, P: PointerWidth> RemotePtr<T, P> {
+    /// Fails with `NullPointer` on a null pointer instead of touching address 0.
+    pub fn read<B: MemoryBackend + ?Sized>(self, mem: &mmg<'_, B>) -> Result<T> {
+        mem.read(self.non_null()?)
+    }
+
+    pub fn write<B: MemoryBackend + ?Sized>(self, mem: &mmg<'_, B>, value: &T) -> Result<usize> {
+        mem.write(self.non_null()?, value)
+    }
+}
+
+impl<T: 'static, P: PointerWidth, Q: PointerWidth> RemotePtr<RemotePtr<T, Q>, P> {
+    /// Follows one level of indirection.
+    pub fn deref<B: MemoryBackend + ?Sized>(self, mem: &mmg<'_, B>) -> Result<RemotePtr<T, Q>> {
+        self.read(mem)
+    }
+}
+
+impl<T, P: PointerWidth> Clone for RemotePtr<T, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, P: PointerWidth> Copy for RemotePtr<T, P> {}
+
+impl<T, P: PointerWidth> PartialEq for RemotePtr<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T, P: PointerWidth> Eq for RemotePtr<T, P> {}
+
+impl<T, P: PointerWidth> Hash for RemotePtr<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl<T, P: PointerWidth> Default for RemotePtr<T, P> {
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T, P: PointerWidth> Debug for RemotePtr<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RemotePtr<{}>(0x{:X})", std::any::type_name::<T>(), self.address())
+    }
+}
+
+impl<T, P: PointerWidth> fmt::Display for RemotePtr<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:X}", self.address())
+    }
+}
+
+/// Elements read per chunk by `RemoteSlice::iter`.
+const ITER_CHUNK_BYTES: usize = 0x1000;
+
+/// `len` consecutive `T`s in the target.
+pub struct RemoteSlice<T> {
+    address: usize,
+    len: usize,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<T> RemoteSlice<T> {
+    #[must_use]
+    #[inline]
+    pub const fn new(address: usize, len: usize) -> Self {
+        Self {
+            address,
+            len,
+            _target: PhantomData,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn address(&self) -> usize {
+        self.address
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn byte_len(&self) -> usize {
+        self.len * std::mem::size_of::<T>()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_ptr(&self) -> RemotePtr<T> {
+        RemotePtr::new(self.address)
+    }
+
+    /// Pointer to element `index`, `None` when out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<RemotePtr<T>> {
+        (index < self.len).then(|| self.as_ptr().offset(index as isize))
+    }
+
+    /// The elements in `[start, end)`, clamped to the slice.
+    #[must_use]
+    pub fn subslice(&self, start: usize, end: usize) -> Self {
+        let end = end.min(self.len);
+        let start = start.min(end);
+        Self::new(self.address + start * std::mem::size_of::<T>(), end - start)
+    }
+}
+
+impl<T: Pod> RemoteSlice<T> {
+    /// Fills `values` from the start of the slice in one read.
+    pub fn read_into<B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>, values: &mut [T]) -> Result<()> {
+        let len = self.len.min(values.len());
+        let values = &mut values[..len];
+        let buffer = slice_bytes_mut(values);
+        let read = mem.read_bytes(self.address, buffer)?;
+
+        if read != buffer.len() {
+            return Err(mm_error::InvalidBufferSize {
+                expected: buffer.len(),
+                actual: read,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads the whole slice in one read.
+    pub fn read<B: MemoryBackend + ?Sized>(&self, mem: &mmg<'_, B>) -> Result<Vec<T>> {
+        let mut values = vec![zeroed::<T>(); self.len];
+        self.read_into(mem, &mut values)?;
+        Ok(values)
+    }
+
+    /// Lazily reads `chunk_len` elements at a time; the last chunk may be shorter.
+    pub fn chunks<'m, 'a, B: MemoryBackend + ?Sized>(
+        &self,
+        mem: &'m mmg<'a, B>,
+        chunk_len: usize,
+    ) -> RemoteChunks<'m, 'a, T, B> {
+        RemoteChunks {
+            mem,
+            rest: Self::new(self.address, self.len),
+            chunk_len: chunk_len.max(1),
+        }
+    }
+
+    /// Element by element, reading a page worth of elements at a time. Stops after
+    /// the first error.
+    pub fn iter<'m, 'a, B: MemoryBackend + ?Sized>(
+        &self,
+        mem: &'m mmg<'a, B>,
+    ) -> impl Iterator<Item = Result<T>> + use<'m, 'a, T, B> {
+        let chunk_len = ITER_CHUNK_BYTES / std::mem::size_of::<T>().max(1);
+        let mut failed = false;
+
+        self.chunks(mem, chunk_len)
+            .map_while(move |chunk| match chunk {
+                _ if failed => None,
+                Ok(values) => Some(values.into_iter().map(Ok).collect::<Vec<_>>()),
+                Err(error) => {
+                    failed = true;
+                    Some(vec![Err(error)])
+                }
+            })
+            .flatten()
+    }
+}
+
+impl<T> Clone for RemoteSlice<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RemoteSlice<T> {}
+
+impl<T> PartialEq for RemoteSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.address, self.len) == (other.address, other.len)
+    }
+}
+
+impl<T> Eq for RemoteSlice<T> {}
+
+impl<T> Debug for RemoteSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RemoteSlice<{}>(0x{:X}, {})", std::any::type_name::<T>(), self.address, self.len)
+    }
+}
+
+/// Iterator returned by `RemoteSlice::chunks`.
+#[derive(Debug)]
+pub struct RemoteChunks<'m, 'a, T, B: ?Sized> {
+    mem: &'m mmg<'a, B>,
+    rest: RemoteSlice<T>,
+    chunk_len: usize,
+}
+
+impl<T: Pod, B: MemoryBackend + ?Sized> Iterator for RemoteChunks<'_, '_, T, B> {
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let chunk = self.rest.subslice(0, self.chunk_len);
+        self.rest = self.rest.subslice(chunk.len(), self.rest.len());
+        Some(chunk.read(self.mem))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = self.rest.len().div_ceil(self.chunk_len);
+        (chunks, Some(chunks))
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// `len` elements at `address` in one read. See `RemoteSlice`.
+    #[inline]
+    pub fn read_slice<T: Pod>(&self, address: usize, len: usize) -> Result<Vec<T>> {
+        RemoteSlice::new(address, len).read(self)
+    }
+}
+
+impl<'a, T: Pod, B: MemoryBackend + ?Sized> TypeReader<'a, T, B> {
+    #[must_use]
+    #[inline]
+    pub fn ptr(&self, address: usize) -> RemotePtr<T> {
+        RemotePtr::new(address)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn slice(&self, address: usize, len: usize) -> RemoteSlice<T> {
+        RemoteSlice::new(address, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    #[test]
+    fn typed_pointers_follow_target_width() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        // 32-bit target: root -> node -> [u16; 4]
+        mem.write::<u32>(0x1000, &0x1100).unwrap();
+        mem.write::<u32>(0x1100, &0x1200).unwrap();
+        mem.write::<[u16; 4]>(0x1200, &[10, 20, 30, 40]).unwrap();
+
+        let root = RemotePtr32::<RemotePtr32<u16>>::new(0x1000);
+        assert_eq!(RemotePtr32::<u16>::WIDTH, 4);
+        assert_eq!(std::mem::size_of::<RemotePtr32<u16>>(), 4);
+
+        let node = root.deref(&mem).unwrap().cast::<RemotePtr32<u16>>();
+        let values = node.deref(&mem).unwrap();
+        assert_eq!(values.address(), 0x1200);
+        assert_eq!(values.offset(2).read(&mem).unwrap(), 30);
+
+        values.offset(3).write(&mem, &41).unwrap();
+        assert_eq!(values.byte_offset(6).read(&mem).unwrap(), 41);
+        assert_eq!(values.offset(3).offset(-3), values);
+
+        let null = RemotePtr::<u32>::null();
+        assert!(matches!(null.read(&mem), Err(mm_error::NullPointer)));
+        assert_eq!(format!("{}", values.cast::<u8>()), "0x1200");
+    }
+
+    #[test]
+    fn slices_read_in_bulk_and_lazily() {
+        let mock = MockProcess::new();
+        let data: Vec<u8> = (0..0x100u32).flat_map(|i| (i as u16).to_le_bytes()).collect();
+        mock.map(0x1000, data, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        let slice = mem.typed::<u16>().slice(0x1000, 0x100);
+        assert_eq!(slice.read(&mem).unwrap()[0xFF], 0xFF);
+        assert_eq!(mock.reads().len(), 1);
+        assert_eq!(slice.get(0x10).unwrap().read(&mem).unwrap(), 0x10);
+        assert!(slice.get(0x100).is_none());
+
+        let chunks: Vec<_> = slice.chunks(&mem, 0x60).map(|c| c.unwrap().len()).collect();
+        assert_eq!(chunks, [0x60, 0x60, 0x40]);
+
+        assert!(slice.iter(&mem).map(Result::unwrap).eq(0..0x100));
+
+        let past_end = RemoteSlice::<u16>::new(0x1100, 0x100);
+        let chunks: Vec<_> = past_end.chunks(&mem, 0x40).map(|c| c.is_ok()).collect();
+        assert_eq!(chunks, [true, true, false, false]);
+        let results: Vec<_> = past_end.iter(&mem).collect();
+        assert!(matches!(results[..], [Err(mm_error::InvalidBufferSize { .. })]));
+
+        assert_eq!(mem.read_slice::<u32>(0x1000, 2).unwrap(), [0x0001_0000, 0x0003_0002]);
+    }
+}
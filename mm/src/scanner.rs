@@ -0,0 +1,743 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use memchr::memmem;
+
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::handle::p_handle;
+use crate::memory::mmg;
+use crate::pattern::{ScanRange, SCAN_CHUNK};
+
+const DEFAULT_SPILL_THRESHOLD: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    /// A byte string of the given length.
+    Bytes(usize),
+}
+
+impl ValueType {
+    #[must_use]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            Self::Bytes(len) => len,
+        }
+    }
+
+    /// Natural alignment, used for slot stepping unless `ScanOptions::alignment` says otherwise.
+    #[must_use]
+    pub const fn alignment(self) -> usize {
+        match self {
+            Self::Bytes(_) => 1,
+            other => other.size(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+}
+
+macro_rules! value_from {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+value_from!(u8 => U8, u16 => U16, u32 => U32, u64 => U64, i8 => I8, i16 => I16, i32 => I32, i64 => I64, f32 => F32, f64 => F64);
+
+impl From<&[u8]> for Value {
+    fn from(value: &[u8]) -> Self {
+        Self::Bytes(value.to_vec())
+    }
+}
+
+impl Value {
+    #[must_use]
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::U8(_) => ValueType::U8,
+            Self::U16(_) => ValueType::U16,
+            Self::U32(_) => ValueType::U32,
+            Self::U64(_) => ValueType::U64,
+            Self::I8(_) => ValueType::I8,
+            Self::I16(_) => ValueType::I16,
+            Self::I32(_) => ValueType::I32,
+            Self::I64(_) => ValueType::I64,
+            Self::F32(_) => ValueType::F32,
+            Self::F64(_) => ValueType::F64,
+            Self::Bytes(bytes) => ValueType::Bytes(bytes.len()),
+        }
+    }
+
+    /// Decodes native-endian bytes; `None` if `bytes` is not exactly `value_type.size()` long.
+    #[must_use]
+    pub fn from_bytes(value_type: ValueType, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != value_type.size() {
+            return None;
+        }
+
+        Some(match value_type {
+            ValueType::U8 => Self::U8(bytes[0]),
+            ValueType::U16 => Self::U16(u16::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::U32 => Self::U32(u32::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::U64 => Self::U64(u64::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::I8 => Self::I8(bytes[0] as i8),
+            ValueType::I16 => Self::I16(i16::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::I32 => Self::I32(i32::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::I64 => Self::I64(i64::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::F32 => Self::F32(f32::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::F64 => Self::F64(f64::from_ne_bytes(bytes.try_into().ok()?)),
+            ValueType::Bytes(_) => Self::Bytes(bytes.to_vec()),
+        })
+    }
+
+    fn number(&self) -> Option<Number> {
+        Some(match *self {
+            Self::U8(v) => Number::Int(v.into()),
+            Self::U16(v) => Number::Int(v.into()),
+            Self::U32(v) => Number::Int(v.into()),
+            Self::U64(v) => Number::Int(v.into()),
+            Self::I8(v) => Number::Int(v.into()),
+            Self::I16(v) => Number::Int(v.into()),
+            Self::I32(v) => Number::Int(v.into()),
+            Self::I64(v) => Number::Int(v.into()),
+            Self::F32(v) => Number::Float(v.into()),
+            Self::F64(v) => Number::Float(v),
+            Self::Bytes(_) => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanPredicate {
+    /// Every slot is a candidate; keeps the current values for later relative scans.
+    Unknown,
+    Exact(Value),
+    /// Inclusive on both ends.
+    InRange(Value, Value),
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+}
+
+impl ScanPredicate {
+    const fn is_relative(&self) -> bool {
+        matches!(self, Self::Changed | Self::Unchanged | Self::Increased | Self::Decreased)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanOptions {
+    /// Slot step; defaults to the natural alignment of the value type.
+    pub alignment: Option<usize>,
+    /// Absolute tolerance for float equality in `Exact`, `Changed` and `Unchanged`.
+    pub float_tolerance: f64,
+    /// Candidate bytes kept in memory before further blocks go to a temporary file.
+    pub spill_threshold: usize,
+    /// Where spill files are created; defaults to `std::env::temp_dir()`.
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            alignment: None,
+            float_tolerance: 1e-6,
+            spill_threshold: DEFAULT_SPILL_THRESHOLD,
+            spill_dir: None,
+        }
+    }
+}
+
+/// Temporary file holding candidate payloads past the spill threshold, removed on drop.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl SpillFile {
+    fn create(options: &ScanOptions) -> std::io::Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = options.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!("mm-scan-{}-{}.bin", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+        let file = File::options().read(true).write(true).create_new(true).open(&path)?;
+
+        Ok(Self { path, file, len: 0 })
+    }
+
+    fn append(&mut self, bytes: &[u8]) -> std::io::Result<u64> {
+        let offset = self.len;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(bytes)?;
+        self.len += bytes.len() as u64;
+        Ok(offset)
+    }
+
+    fn load(&self, offset: u64, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut bytes = vec![0u8; len];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn spill_error(error: std::io::Error) -> mm_error {
+    mm_error::ScanSpillFailed(error.to_string())
+}
+
+#[derive(Debug)]
+enum Payload {
+    Memory(Vec<u8>),
+    Disk { offset: u64, len: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BlockKind {
+    /// Every aligned slot starting in `[base, base + slots_len)`; the payload is the raw
+    /// memory, possibly running a few bytes past `slots_len` so the last slots are whole.
+    Dense { slots_len: usize },
+    /// `count` little-endian `u32` offsets from `base`, followed by `count` values.
+    Sparse,
+}
+
+#[derive(Debug)]
+struct CandidateBlock {
+    base: usize,
+    kind: BlockKind,
+    count: usize,
+    payload: Payload,
+}
+
+/// Candidate storage for one scan generation: blocks plus the file any of them spilled to.
+#[derive(Debug, Default)]
+struct CandidateSet {
+    blocks: Vec<CandidateBlock>,
+    spill: Option<SpillFile>,
+    in_memory: usize,
+}
+
+impl CandidateSet {
+    fn push(&mut self, options: &ScanOptions, base: usize, kind: BlockKind, count: usize, bytes: Vec<u8>) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        let payload = if self.in_memory + bytes.len() > options.spill_threshold {
+            if self.spill.is_none() {
+                self.spill = Some(SpillFile::create(options).map_err(spill_error)?);
+            }
+            let spill = self.spill.as_mut().expect("spill file was just created");
+            let offset = spill.append(&bytes).map_err(spill_error)?;
+            Payload::Disk { offset, len: bytes.len() }
+        } else {
+            self.in_memory += bytes.len();
+            Payload::Memory(bytes)
+        };
+
+        self.blocks.push(CandidateBlock { base, kind, count, payload });
+        Ok(())
+    }
+
+    fn payload<'s>(&'s self, block: &'s CandidateBlock) -> Result<Cow<'s, [u8]>> {
+        match &block.payload {
+            Payload::Memory(bytes) => Ok(Cow::Borrowed(bytes)),
+            Payload::Disk { offset, len } => self
+                .spill
+                .as_ref()
+                .ok_or_else(|| mm_error::ScanSpillFailed("spill file missing".to_string()))?
+                .load(*offset, *len)
+                .map(Cow::Owned)
+                .map_err(spill_error),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.blocks.iter().map(|b| b.count).sum()
+    }
+}
+
+/// Slot offsets and their recorded values for one block.
+fn slots<'p>(
+    base: usize,
+    kind: BlockKind,
+    count: usize,
+    payload: &'p [u8],
+    size: usize,
+    alignment: usize,
+) -> Box<dyn Iterator<Item = (usize, &'p [u8])> + 'p> {
+    match kind {
+        BlockKind::Dense { slots_len } => {
+            let first = base.next_multiple_of(alignment) - base;
+            Box::new(
+                (first..slots_len)
+                    .step_by(alignment)
+                    .map_while(move |offset| Some((offset, payload.get(offset..offset + size)?))),
+            )
+        }
+        BlockKind::Sparse => {
+            let (offsets, values) = payload.split_at(count * 4);
+            Box::new(
+                offsets
+                    .chunks_exact(4)
+                    .map(|offset| u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize)
+                    .zip(values.chunks_exact(size)),
+            )
+        }
+    }
+}
+
+/// Surviving slots of one block, encoded as a `BlockKind::Sparse` payload once finished.
+#[derive(Debug, Default)]
+struct SparseHits {
+    offsets: Vec<u8>,
+    values: Vec<u8>,
+    count: usize,
+}
+
+impl SparseHits {
+    fn push(&mut self, offset: usize, value: &[u8]) {
+        self.offsets.extend_from_slice(&(offset as u32).to_le_bytes());
+        self.values.extend_from_slice(value);
+        self.count += 1;
+    }
+
+    fn into_payload(mut self) -> Vec<u8> {
+        self.offsets.append(&mut self.values);
+        self.offsets
+    }
+}
+
+/// Iterative "first scan / next scan" search for a value of one type.
+#[derive(Debug)]
+pub struct ValueScanner<'a, B: ?Sized = p_handle> {
+    mem: mmg<'a, B>,
+    value_type: ValueType,
+    options: ScanOptions,
+    candidates: CandidateSet,
+}
+
+impl<'a, B: MemoryBackend + ?Sized> ValueScanner<'a, B> {
+    #[must_use]
+    pub fn new(backend: &'a B, value_type: ValueType) -> Self {
+        Self {
+            mem: mmg::new(backend),
+            value_type,
+            options: ScanOptions::default(),
+            candidates: CandidateSet::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    fn alignment(&self) -> usize {
+        self.options.alignment.unwrap_or(self.value_type.alignment()).max(1)
+    }
+
+    /// Number of remaining candidates.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.candidates.count()
+    }
+
+    /// Whether any candidates currently live in a spill file.
+    #[must_use]
+    pub fn is_spilled(&self) -> bool {
+        self.candidates.spill.is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.candidates = CandidateSet::default();
+    }
+
+    fn check(&self, predicate: &ScanPredicate) -> Result<()> {
+        let values: &[&Value] = match predicate {
+            ScanPredicate::Exact(value) => &[value],
+            ScanPredicate::InRange(low, high) => &[low, high],
+            _ => &[],
+        };
+        if values.iter().any(|v| v.value_type() != self.value_type) {
+            return Err(mm_error::InvalidScan("predicate value does not match the scanner value type"));
+        }
+
+        let ordered = matches!(
+            predicate,
+            ScanPredicate::InRange(..) | ScanPredicate::Increased | ScanPredicate::Decreased
+        );
+        if ordered && matches!(self.value_type, ValueType::Bytes(_)) {
+            return Err(mm_error::InvalidScan("ordered predicates on byte strings"));
+        }
+
+        if self.value_type.size() == 0 {
+            return Err(mm_error::InvalidScan("zero-sized scan value"));
+        }
+
+        Ok(())
+    }
+
+    fn equal(&self, a: &[u8], b: &[u8]) -> bool {
+        match (self.decode(a), self.decode(b)) {
+            (Some(Number::Float(a)), Some(Number::Float(b))) => (a - b).abs() <= self.options.float_tolerance,
+            _ => a == b,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Number> {
+        Value::from_bytes(self.value_type, bytes)?.number()
+    }
+
+    fn test(&self, predicate: &ScanPredicate, old: &[u8], new: &[u8]) -> bool {
+        let ordered = |a: Option<Number>, b: Option<Number>| match (a, b) {
+            (Some(Number::Int(a)), Some(Number::Int(b))) => a.partial_cmp(&b),
+            (Some(Number::Float(a)), Some(Number::Float(b))) => a.partial_cmp(&b),
+            _ => None,
+        };
+
+        match predicate {
+            ScanPredicate::Unknown => true,
+            ScanPredicate::Exact(value) => match value {
+                Value::Bytes(bytes) => new == bytes.as_slice(),
+                value => self.equal(new, &self.encode(value)),
+            },
+            ScanPredicate::InRange(low, high) => {
+                let new = self.decode(new);
+                ordered(low.number(), new).is_some_and(|o| o.is_le()) && ordered(new, high.number()).is_some_and(|o| o.is_le())
+            }
+            ScanPredicate::Changed => !self.equal(old, new),
+            ScanPredicate::Unchanged => self.equal(old, new),
+            ScanPredicate::Increased => ordered(self.decode(new), self.decode(old)).is_some_and(|o| o.is_gt()),
+            ScanPredicate::Decreased => ordered(self.decode(new), self.decode(old)).is_some_and(|o| o.is_lt()),
+        }
+    }
+
+    fn encode(&self, value: &Value) -> Vec<u8> {
+        match *value {
+            Value::U8(v) => vec![v],
+            Value::U16(v) => v.to_ne_bytes().to_vec(),
+            Value::U32(v) => v.to_ne_bytes().to_vec(),
+            Value::U64(v) => v.to_ne_bytes().to_vec(),
+            Value::I8(v) => v.to_ne_bytes().to_vec(),
+            Value::I16(v) => v.to_ne_bytes().to_vec(),
+            Value::I32(v) => v.to_ne_bytes().to_vec(),
+            Value::I64(v) => v.to_ne_bytes().to_vec(),
+            Value::F32(v) => v.to_ne_bytes().to_vec(),
+            Value::F64(v) => v.to_ne_bytes().to_vec(),
+            Value::Bytes(ref bytes) => bytes.clone(),
+        }
+    }
+
+    /// Scans every readable byte of `range`, replacing any previous candidates.
+    /// Relative predicates need a previous scan and are rejected here.
+    pub fn first_scan(&mut self, range: impl Into<ScanRange>, predicate: &ScanPredicate) -> Result<usize> {
+        self.check(predicate)?;
+        if predicate.is_relative() {
+            return Err(mm_error::InvalidScan("relative predicate on a first scan"));
+        }
+
+        let size = self.value_type.size();
+        let alignment = self.alignment();
+        let mut candidates = CandidateSet::default();
+
+        for &(start, end) in range.into().spans() {
+            let mut cursor = start;
+
+            while cursor < end {
+                let slots_len = SCAN_CHUNK.min(end - cursor);
+                let mut chunk = vec![0u8; slots_len + size - 1];
+                let read = self.mem.read_bytes(cursor, &mut chunk).unwrap_or(0);
+                chunk.truncate(read);
+
+                if read >= size {
+                    let kind = BlockKind::Dense {
+                        slots_len: slots_len.min(read - size + 1),
+                    };
+
+                    if *predicate == ScanPredicate::Unknown {
+                        let count = slots(cursor, kind, 0, &chunk, size, alignment).count();
+                        candidates.push(&self.options, cursor, kind, count, chunk)?;
+                    } else {
+                        let hits = self.first_hits(cursor, kind, &chunk, predicate);
+                        candidates.push(&self.options, cursor, BlockKind::Sparse, hits.count, hits.into_payload())?;
+                    }
+                }
+
+                cursor = if read < slots_len {
+                    self.mem.skip_unreadable(cursor + read).min(end)
+                } else {
+                    cursor + slots_len
+                };
+            }
+        }
+
+        self.candidates = candidates;
+        Ok(self.count())
+    }
+
+    fn first_hits(&self, base: usize, kind: BlockKind, chunk: &[u8], predicate: &ScanPredicate) -> SparseHits {
+        let alignment = self.alignment();
+        let mut hits = SparseHits::default();
+
+        // byte strings go through memmem instead of testing every slot
+        if let (ScanPredicate::Exact(Value::Bytes(needle)), BlockKind::Dense { slots_len }) = (predicate, kind) {
+            for offset in memmem::find_iter(chunk, needle) {
+                if offset < slots_len && (base + offset).is_multiple_of(alignment) {
+                    hits.push(offset, needle);
+                }
+            }
+            return hits;
+        }
+
+        for (offset, value) in slots(base, kind, 0, chunk, self.value_type.size(), alignment) {
+            if self.test(predicate, value, value) {
+                hits.push(offset, value);
+            }
+        }
+        hits
+    }
+
+    /// Re-reads every candidate and keeps those for which `predicate` holds against the
+    /// value recorded by the previous scan. Candidates that became unreadable are dropped.
+    pub fn next_scan(&mut self, predicate: &ScanPredicate) -> Result<usize> {
+        self.check(predicate)?;
+
+        let size = self.value_type.size();
+        let alignment = self.alignment();
+        let previous = std::mem::take(&mut self.candidates);
+        let mut candidates = CandidateSet::default();
+
+        for block in &previous.blocks {
+            let payload = previous.payload(block)?;
+            let old = || slots(block.base, block.kind, block.count, &payload, size, alignment);
+            let (Some((first, _)), Some((last, _))) = (old().next(), old().last()) else {
+                continue;
+            };
+
+            let mut current = vec![0u8; last + size - first];
+            let read = self.mem.read_bytes(block.base + first, &mut current).unwrap_or(0);
+            let mut single = vec![0u8; size];
+            let mut hits = SparseHits::default();
+
+            for (offset, old_value) in old() {
+                let at = offset - first;
+                let new_value = if at + size <= read {
+                    &current[at..at + size]
+                } else if self.mem.read_bytes(block.base + offset, &mut single).is_ok_and(|n| n == size) {
+                    &single[..]
+                } else {
+                    continue;
+                };
+
+                if self.test(predicate, old_value, new_value) {
+                    hits.push(offset, new_value);
+                }
+            }
+
+            candidates.push(&self.options, block.base, BlockKind::Sparse, hits.count, hits.into_payload())?;
+        }
+
+        self.candidates = candidates;
+        Ok(self.count())
+    }
+
+    /// Up to `limit` candidates with the value each had at the last scan.
+    pub fn results(&self, limit: usize) -> Result<Vec<(usize, Value)>> {
+        let size = self.value_type.size();
+        let alignment = self.alignment();
+        let mut results = Vec::new();
+
+        for block in &self.candidates.blocks {
+            if results.len() >= limit {
+                break;
+            }
+            let payload = self.candidates.payload(block)?;
+            results.extend(
+                slots(block.base, block.kind, block.count, &payload, size, alignment)
+                    .take(limit - results.len())
+                    .filter_map(|(offset, bytes)| Some((block.base + offset, Value::from_bytes(self.value_type, bytes)?))),
+            );
+        }
+
+        Ok(results)
+    }
+
+    pub fn addresses(&self) -> Result<Vec<usize>> {
+        Ok(self.results(usize::MAX)?.into_iter().map(|(address, _)| address).collect())
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    #[must_use]
+    #[inline]
+    pub fn value_scanner(&self, value_type: ValueType) -> ValueScanner<'a, B> {
+        ValueScanner::new(self.backend(), value_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x100, Protection::READ_WRITE);
+        mock.map_zeroed(0x2000, 0x100, Protection::NONE);
+        mock.map_zeroed(0x3000, 0x100, Protection::READ_WRITE);
+        mock
+    }
+
+    #[test]
+    fn exact_then_relative_scans_narrow_integers() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        for address in [0x1010, 0x1040, 0x3008] {
+            mem.write::<u32>(address, &100).unwrap();
+        }
+
+        let mut scanner = mem.value_scanner(ValueType::U32);
+        assert_eq!(scanner.first_scan(0x1000..0x4000, &ScanPredicate::Exact(100u32.into())).unwrap(), 3);
+
+        mem.write::<u32>(0x1010, &150).unwrap();
+        mem.write::<u32>(0x3008, &50).unwrap();
+        assert_eq!(scanner.next_scan(&ScanPredicate::Changed).unwrap(), 2);
+        assert_eq!(scanner.results(10).unwrap(), [(0x1010, Value::U32(150)), (0x3008, Value::U32(50))]);
+
+        assert_eq!(scanner.next_scan(&ScanPredicate::Unchanged).unwrap(), 2);
+        assert_eq!(scanner.next_scan(&ScanPredicate::InRange(100u32.into(), 200u32.into())).unwrap(), 1);
+        assert_eq!(scanner.addresses().unwrap(), [0x1010]);
+
+        assert!(matches!(
+            scanner.next_scan(&ScanPredicate::Exact(1i64.into())),
+            Err(mm_error::InvalidScan(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_initial_value_tracks_increases_and_decreases() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        mem.write::<i16>(0x1020, &-5).unwrap();
+
+        let mut scanner = mem.value_scanner(ValueType::I16);
+        assert!(matches!(
+            scanner.first_scan(0x1000..0x1100, &ScanPredicate::Increased),
+            Err(mm_error::InvalidScan(_))
+        ));
+        assert_eq!(scanner.first_scan(0x1000..0x1100, &ScanPredicate::Unknown).unwrap(), 0x80);
+
+        mem.write::<i16>(0x1020, &7).unwrap();
+        mem.write::<i16>(0x1030, &-1).unwrap();
+        assert_eq!(scanner.next_scan(&ScanPredicate::Increased).unwrap(), 1);
+        assert_eq!(scanner.results(1).unwrap(), [(0x1020, Value::I16(7))]);
+
+        mem.write::<i16>(0x1020, &3).unwrap();
+        assert_eq!(scanner.next_scan(&ScanPredicate::Decreased).unwrap(), 1);
+    }
+
+    #[test]
+    fn floats_use_tolerance_and_bytes_use_memmem() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        mem.write::<f32>(0x1004, &1.5).unwrap();
+        mem.write::<f32>(0x3000, &1.500_000_1).unwrap();
+        mem.write::<[u8; 5]>(0x10F9, b"hello").unwrap();
+
+        let mut floats = mem.value_scanner(ValueType::F32);
+        assert_eq!(floats.first_scan(0x1000..0x4000, &ScanPredicate::Exact(1.5f32.into())).unwrap(), 2);
+
+        let mut strict = mem.value_scanner(ValueType::F32).with_options(ScanOptions {
+            float_tolerance: 0.0,
+            ..ScanOptions::default()
+        });
+        assert_eq!(strict.first_scan(0x1000..0x4000, &ScanPredicate::Exact(1.5f32.into())).unwrap(), 1);
+
+        let mut bytes = mem.value_scanner(ValueType::Bytes(5));
+        assert_eq!(bytes.first_scan(0x1000..0x4000, &ScanPredicate::Exact(b"hello"[..].into())).unwrap(), 1);
+        assert_eq!(bytes.addresses().unwrap(), [0x10F9]);
+        assert!(matches!(bytes.next_scan(&ScanPredicate::Increased), Err(mm_error::InvalidScan(_))));
+    }
+
+    #[test]
+    fn large_candidate_sets_spill_to_disk() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+
+        let mut scanner = mem.value_scanner(ValueType::U8).with_options(ScanOptions {
+            spill_threshold: 0x80,
+            ..ScanOptions::default()
+        });
+        assert_eq!(scanner.first_scan(0x1000..0x4000, &ScanPredicate::Unknown).unwrap(), 0x200);
+        assert!(scanner.is_spilled());
+        let spill_path = scanner.candidates.spill.as_ref().unwrap().path.clone();
+        assert!(spill_path.exists());
+
+        mem.write::<u8>(0x30FF, &9).unwrap();
+        assert_eq!(scanner.next_scan(&ScanPredicate::Changed).unwrap(), 1);
+        assert_eq!(scanner.results(10).unwrap(), [(0x30FF, Value::U8(9))]);
+        assert!(!scanner.is_spilled());
+        assert!(!spill_path.exists());
+    }
+}
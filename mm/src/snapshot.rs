@@ -0,0 +1,323 @@
+use std::ops::Range;
+use std::time::Instant;
+
+use crate::backend::MemoryBackend;
+use crate::error::Result;
+use crate::memory::mmg;
+use crate::pattern::{ScanRange, SCAN_CHUNK};
+
+/// One contiguous readable run of captured memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotBlock {
+    pub base: usize,
+    pub data: Vec<u8>,
+}
+
+impl SnapshotBlock {
+    #[must_use]
+    #[inline]
+    pub fn end_address(&self) -> usize {
+        self.base + self.data.len()
+    }
+}
+
+/// Copy of a set of address ranges taken at one moment. Unreadable memory inside the
+/// captured range is simply absent, so a snapshot is a sorted list of blocks.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    blocks: Vec<SnapshotBlock>,
+    taken_at: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedRange {
+    pub address: usize,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+impl ChangedRange {
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.old.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.old.is_empty()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn end_address(&self) -> usize {
+        self.address + self.len()
+    }
+}
+
+/// Narrows a diff. With an alignment of `n`, memory is compared in `n`-byte units at
+/// addresses aligned to `n` and a unit is reported whole when any byte in it changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffFilter {
+    within: Option<Range<usize>>,
+    alignment: usize,
+    min_len: usize,
+}
+
+impl Default for DiffFilter {
+    fn default() -> Self {
+        Self {
+            within: None,
+            alignment: 1,
+            min_len: 1,
+        }
+    }
+}
+
+impl DiffFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only compare addresses inside `range`, e.g. one region or module.
+    #[must_use]
+    pub fn within(mut self, range: Range<usize>) -> Self {
+        self.within = Some(range);
+        self
+    }
+
+    #[must_use]
+    pub fn aligned(mut self, alignment: usize) -> Self {
+        self.alignment = alignment.max(1);
+        self
+    }
+
+    /// Drop changed runs shorter than `min_len` bytes.
+    #[must_use]
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len.max(1);
+        self
+    }
+}
+
+impl Snapshot {
+    /// Reads every readable byte of `range`, skipping whatever faults.
+    pub fn capture<B: MemoryBackend + ?Sized>(mem: &mmg<'_, B>, range: impl Into<ScanRange>) -> Result<Self> {
+        let mut blocks: Vec<SnapshotBlock> = Vec::new();
+
+        for &(start, end) in range.into().spans() {
+            let mut cursor = start;
+
+            while cursor < end {
+                let len = SCAN_CHUNK.min(end - cursor);
+                let mut chunk = vec![0u8; len];
+                let read = mem.read_bytes(cursor, &mut chunk).unwrap_or(0);
+                chunk.truncate(read);
+
+                match blocks.last_mut() {
+                    _ if read == 0 => {}
+                    Some(block) if block.end_address() == cursor => block.data.extend(chunk),
+                    _ => blocks.push(SnapshotBlock { base: cursor, data: chunk }),
+                }
+
+                cursor = if read < len {
+                    mem.skip_unreadable(cursor + read).min(end)
+                } else {
+                    cursor + read
+                };
+            }
+        }
+
+        Ok(Self {
+            blocks,
+            taken_at: Instant::now(),
+        })
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn blocks(&self) -> &[SnapshotBlock] {
+        &self.blocks
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn taken_at(&self) -> Instant {
+        self.taken_at
+    }
+
+    /// Total number of captured bytes.
+    #[must_use]
+    pub fn byte_len(&self) -> usize {
+        self.blocks.iter().map(|b| b.data.len()).sum()
+    }
+
+    /// Captured bytes at `[address, address + len)`, if all of them were captured.
+    #[must_use]
+    pub fn bytes(&self, address: usize, len: usize) -> Option<&[u8]> {
+        let block = self.blocks.iter().find(|b| address >= b.base && address < b.end_address())?;
+        let offset = address - block.base;
+        block.data.get(offset..offset.checked_add(len)?)
+    }
+
+    /// Every changed run between `self` and `newer`, over the addresses both captured.
+    #[must_use]
+    pub fn diff(&self, newer: &Self) -> Vec<ChangedRange> {
+        self.diff_with(newer, &DiffFilter::default())
+    }
+
+    #[must_use]
+    pub fn diff_with(&self, newer: &Self, filter: &DiffFilter) -> Vec<ChangedRange> {
+        let mut changes = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        // both block lists are sorted and disjoint: walk them together, stepping past
+        // whichever block ends first
+        while let (Some(old), Some(new)) = (self.blocks.get(i), newer.blocks.get(j)) {
+            let mut start = old.base.max(new.base);
+            let mut end = old.end_address().min(new.end_address());
+            if let Some(within) = &filter.within {
+                start = start.max(within.start);
+                end = end.min(within.end);
+            }
+
+            if start < end {
+                diff_span(old, new, start, end, filter, &mut changes);
+            }
+
+            if old.end_address() <= new.end_address() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        changes
+    }
+}
+
+fn diff_span(
+    old: &SnapshotBlock,
+    new: &SnapshotBlock,
+    start: usize,
+    end: usize,
+    filter: &DiffFilter,
+    changes: &mut Vec<ChangedRange>,
+) {
+    let old_at = |range: Range<usize>| &old.data[range.start - old.base..range.end - old.base];
+    let new_at = |range: Range<usize>| &new.data[range.start - new.base..range.end - new.base];
+
+    let mut emit = |run: Range<usize>| {
+        if run.len() >= filter.min_len {
+            changes.push(ChangedRange {
+                address: run.start,
+                old: old_at(run.clone()).to_vec(),
+                new: new_at(run).to_vec(),
+            });
+        }
+    };
+
+    let mut run: Option<Range<usize>> = None;
+    let mut unit_start = start;
+
+    while unit_start < end {
+        let unit_end = (unit_start - unit_start % filter.alignment + filter.alignment).min(end);
+        let unit = unit_start..unit_end;
+
+        if old_at(unit.clone()) != new_at(unit.clone()) {
+            run = Some(run.map_or(unit.clone(), |r| r.start..unit.end));
+        } else if let Some(finished) = run.take() {
+            emit(finished);
+        }
+
+        unit_start = unit_end;
+    }
+
+    if let Some(finished) = run {
+        emit(finished);
+    }
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// See `Snapshot::capture`.
+    #[inline]
+    pub fn snapshot(&self, range: impl Into<ScanRange>) -> Result<Snapshot> {
+        Snapshot::capture(self, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::module::ModuleInfo;
+    use crate::region::Protection;
+
+    fn process() -> MockProcess {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x100, Protection::READ_WRITE);
+        mock.map_zeroed(0x1100, 0x100, Protection::NONE);
+        mock.map_zeroed(0x1200, 0x100, Protection::READ_WRITE);
+        mock
+    }
+
+    #[test]
+    fn capture_skips_unreadable_memory() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        let module = ModuleInfo::new("game.so".into(), 0x1000, 0x300, 0);
+
+        let snapshot = mem.snapshot(&module).unwrap();
+        let spans: Vec<_> = snapshot.blocks().iter().map(|b| (b.base, b.end_address())).collect();
+        assert_eq!(spans, [(0x1000, 0x1100), (0x1200, 0x1300)]);
+        assert_eq!(snapshot.byte_len(), 0x200);
+        assert_eq!(snapshot.bytes(0x10F8, 8), Some(&[0u8; 8][..]));
+        assert_eq!(snapshot.bytes(0x10FC, 8), None);
+
+        let regions: Vec<_> = mem.query_regions().unwrap().collect();
+        assert_eq!(mem.snapshot(regions).unwrap().blocks().len(), 2);
+    }
+
+    #[test]
+    fn diff_reports_changed_runs_with_filters() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        let before = mem.snapshot(0x1000..0x1300).unwrap();
+
+        mem.write::<u8>(0x1005, &1).unwrap();
+        mem.write::<[u8; 3]>(0x1010, &[1, 2, 3]).unwrap();
+        mem.write::<u32>(0x1204, &0xDEAD_BEEF).unwrap();
+        let after = mem.snapshot(0x1000..0x1300).unwrap();
+
+        let all: Vec<_> = before.diff(&after).iter().map(|c| (c.address, c.len())).collect();
+        assert_eq!(all, [(0x1005, 1), (0x1010, 3), (0x1204, 4)]);
+
+        let changed = before.diff_with(&after, &DiffFilter::new().aligned(4));
+        let aligned: Vec<_> = changed.iter().map(|c| (c.address, c.len())).collect();
+        assert_eq!(aligned, [(0x1004, 4), (0x1010, 4), (0x1204, 4)]);
+        assert_eq!(changed[0].new, [0, 1, 0, 0]);
+
+        let long = before.diff_with(&after, &DiffFilter::new().min_len(3).within(0x1000..0x1100));
+        assert_eq!(long.len(), 1);
+        assert_eq!((long[0].address, &long[0].old[..], &long[0].new[..]), (0x1010, &[0u8; 3][..], &[1u8, 2, 3][..]));
+    }
+
+    #[test]
+    fn diff_walks_differently_split_blocks() {
+        let mock = process();
+        let mem = mmg::new(&mock);
+        let before = mem.snapshot(0x1000..0x1300).unwrap();
+
+        for address in [0x1010, 0x1090, 0x1210, 0x1290] {
+            mem.write::<u8>(address, &1).unwrap();
+        }
+        let after = mem.snapshot(0x1080..0x1280).unwrap();
+
+        let changed: Vec<_> = before.diff(&after).iter().map(|c| c.address).collect();
+        assert_eq!(changed, [0x1090, 0x1210]);
+        let changed: Vec<_> = after.diff(&before).iter().map(|c| c.address).collect();
+        assert_eq!(changed, [0x1090, 0x1210]);
+    }
+}
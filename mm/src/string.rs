@@ -0,0 +1,239 @@
+use crate::backend::MemoryBackend;
+use crate::error::{mm_error, Result};
+use crate::memory::mmg;
+
+/// Strings are read up to the next page boundary at a time, so a short string at the end
+/// of a mapping never needs the (possibly unmapped) bytes after it.
+const STRING_PAGE: usize = 0x1000;
+
+/// Encoding of a null-terminated string in the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    Utf8,
+    /// ISO-8859-1: every byte is the code point of the same value.
+    Latin1,
+    Utf16Le,
+    Utf32Le,
+    /// Needs the `shift-jis` feature; without it reads fail with `Unsupported`.
+    ShiftJis,
+}
+
+impl StringEncoding {
+    /// Bytes per code unit, which is also the size of the terminator.
+    #[must_use]
+    pub const fn unit_size(self) -> usize {
+        match self {
+            Self::Utf8 | Self::Latin1 | Self::ShiftJis => 1,
+            Self::Utf16Le => 2,
+            Self::Utf32Le => 4,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Latin1 => "Latin-1",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf32Le => "UTF-32LE",
+            Self::ShiftJis => "Shift-JIS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DecodeMode {
+    /// Invalid sequences fail the read with `StringDecodeFailed`.
+    #[default]
+    Strict,
+    /// Invalid sequences become U+FFFD.
+    Lossy,
+}
+
+impl<'a, B: MemoryBackend + ?Sized> mmg<'a, B> {
+    /// Raw code units of the null-terminated string at `address`, without the terminator,
+    /// reading at most `max_units` units one page at a time. Stops early at memory that
+    /// cannot be read, which only fails the call when not even the first byte is readable.
+    pub fn read_terminated(&self, address: usize, max_units: usize, unit_size: usize) -> Result<Vec<u8>> {
+        let unit_size = unit_size.max(1);
+        let max_bytes = max_units.saturating_mul(unit_size);
+        let mut bytes = Vec::new();
+        let mut scanned = 0;
+
+        while bytes.len() < max_bytes {
+            let cursor = address.wrapping_add(bytes.len());
+            let page_left = STRING_PAGE - cursor % STRING_PAGE;
+            let start = bytes.len();
+            bytes.resize(start + page_left.min(max_bytes - start), 0);
+
+            let read = match self.read_bytes(cursor, &mut bytes[start..]) {
+                Ok(read) => read,
+                Err(error) if start == 0 => return Err(error),
+                Err(_) => 0,
+            };
+            bytes.truncate(start + read);
+
+            let units = bytes[scanned..].chunks_exact(unit_size);
+            if let Some(index) = units.clone().position(|unit| unit.iter().all(|&b| b == 0)) {
+                bytes.truncate(scanned + index * unit_size);
+                return Ok(bytes);
+            }
+            scanned += units.len() * unit_size;
+
+            if read < page_left.min(max_bytes - start) {
+                break;
+            }
+        }
+
+        bytes.truncate(bytes.len() - bytes.len() % unit_size);
+        Ok(bytes)
+    }
+
+    /// Null-terminated string of at most `max_units` code units in `encoding`.
+    pub fn read_string_as(
+        &self,
+        address: usize,
+        max_units: usize,
+        encoding: StringEncoding,
+        mode: DecodeMode,
+    ) -> Result<String> {
+        if cfg!(not(feature = "shift-jis")) && encoding == StringEncoding::ShiftJis {
+            return Err(mm_error::Unsupported("Shift-JIS without the shift-jis feature"));
+        }
+
+        let bytes = self.read_terminated(address, max_units, encoding.unit_size())?;
+        decode(&bytes, encoding, mode).ok_or(mm_error::StringDecodeFailed {
+            address,
+            encoding: encoding.name(),
+        })
+    }
+
+    /// `read_string` with invalid UTF-8 replaced instead of failing.
+    #[inline]
+    pub fn read_string_lossy(&self, address: usize, max_length: usize) -> Result<String> {
+        self.read_string_as(address, max_length, StringEncoding::Utf8, DecodeMode::Lossy)
+    }
+
+    /// `read_wstring` with unpaired surrogates replaced instead of failing.
+    #[inline]
+    pub fn read_wstring_lossy(&self, address: usize, max_chars: usize) -> Result<String> {
+        self.read_string_as(address, max_chars, StringEncoding::Utf16Le, DecodeMode::Lossy)
+    }
+}
+
+/// `None` when strict decoding hits an invalid sequence.
+fn decode(bytes: &[u8], encoding: StringEncoding, mode: DecodeMode) -> Option<String> {
+    let lossy = mode == DecodeMode::Lossy;
+
+    match encoding {
+        StringEncoding::Utf8 if lossy => Some(String::from_utf8_lossy(bytes).into_owned()),
+        StringEncoding::Utf8 => String::from_utf8(bytes.to_vec()).ok(),
+        StringEncoding::Latin1 => Some(bytes.iter().map(|&b| char::from(b)).collect()),
+        StringEncoding::Utf16Le => {
+            let units = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+            char::decode_utf16(units)
+                .map(|c| c.ok().or(lossy.then_some(char::REPLACEMENT_CHARACTER)))
+                .collect()
+        }
+        StringEncoding::Utf32Le => bytes
+            .chunks_exact(4)
+            .map(|unit| u32::from_le_bytes([unit[0], unit[1], unit[2], unit[3]]))
+            .map(|unit| char::from_u32(unit).or(lossy.then_some(char::REPLACEMENT_CHARACTER)))
+            .collect(),
+        #[cfg(feature = "shift-jis")]
+        StringEncoding::ShiftJis if lossy => Some(encoding_rs::SHIFT_JIS.decode_without_bom_handling(bytes).0.into_owned()),
+        #[cfg(feature = "shift-jis")]
+        StringEncoding::ShiftJis => encoding_rs::SHIFT_JIS
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| text.into_owned()),
+        #[cfg(not(feature = "shift-jis"))]
+        StringEncoding::ShiftJis => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockProcess;
+    use crate::region::Protection;
+
+    #[test]
+    fn reads_stop_at_the_terminator_before_unmapped_memory() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x2000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        mem.write_string(0x2FF8, "tail").unwrap();
+        assert_eq!(mem.read_string(0x2FF8, 4096).unwrap(), "tail");
+        let reads: Vec<_> = mock.reads().iter().map(|r| (r.address, r.len)).collect();
+        assert_eq!(reads, [(0x2FF8, 8)]);
+
+        mem.write_bytes(0x2FFC, b"edge").unwrap();
+        assert_eq!(mem.read_string(0x2FF8, 4096).unwrap(), "tailedge");
+        assert!(mem.read_string(0x3000, 16).is_err());
+
+        mem.write_wstring(0x1FFA, "wide").unwrap();
+        assert_eq!(mem.read_wstring(0x1FFA, 64).unwrap(), "wide");
+        assert_eq!(mem.read_wstring(0x1FFA, 2).unwrap(), "wi");
+    }
+
+    #[test]
+    fn strict_and_lossy_decoding() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        mem.write_bytes(0x1000, b"bad \xFF byte\0").unwrap();
+        assert!(matches!(
+            mem.read_string(0x1000, 64),
+            Err(mm_error::StringDecodeFailed { address: 0x1000, encoding: "UTF-8" })
+        ));
+        assert_eq!(mem.read_string_lossy(0x1000, 64).unwrap(), "bad \u{FFFD} byte");
+
+        mem.write::<[u16; 3]>(0x1100, &[0x61, 0xD800, 0]).unwrap();
+        assert!(mem.read_wstring(0x1100, 8).is_err());
+        assert_eq!(mem.read_wstring_lossy(0x1100, 8).unwrap(), "a\u{FFFD}");
+    }
+
+    #[test]
+    fn other_encodings() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+        let read = |address, encoding| mem.read_string_as(address, 64, encoding, DecodeMode::Strict);
+
+        mem.write_bytes(0x1000, b"caf\xE9\0").unwrap();
+        assert_eq!(read(0x1000, StringEncoding::Latin1).unwrap(), "café");
+
+        mem.write::<[u32; 3]>(0x1100, &[0x1F600, 0x41, 0]).unwrap();
+        assert_eq!(read(0x1100, StringEncoding::Utf32Le).unwrap(), "\u{1F600}A");
+        mem.write::<u32>(0x1100, &0x11_0000).unwrap();
+        assert!(read(0x1100, StringEncoding::Utf32Le).is_err());
+    }
+
+    #[cfg(not(feature = "shift-jis"))]
+    #[test]
+    fn shift_jis_needs_its_feature() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+
+        let read = mem.read_string_as(0x1000, 64, StringEncoding::ShiftJis, DecodeMode::Strict);
+        assert!(matches!(read, Err(mm_error::Unsupported(_))));
+        assert!(mock.reads().is_empty());
+    }
+
+    #[cfg(feature = "shift-jis")]
+    #[test]
+    fn shift_jis() {
+        let mock = MockProcess::new();
+        mock.map_zeroed(0x1000, 0x1000, Protection::READ_WRITE);
+        let mem = mmg::new(&mock);
+        let read = |address, encoding| mem.read_string_as(address, 64, encoding, DecodeMode::Strict);
+
+        mem.write_bytes(0x1200, b"\x93\xFA\x96\x7B\x8C\xEA\0").unwrap();
+        assert_eq!(read(0x1200, StringEncoding::ShiftJis).unwrap(), "日本語");
+        mem.write_bytes(0x1200, b"\x93\0").unwrap();
+        assert!(read(0x1200, StringEncoding::ShiftJis).is_err());
+    }
+}